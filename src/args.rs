@@ -1,13 +1,33 @@
 use crate::{
-    chunk::Chunk,
-    chunk_type::ChunkType,
-    png::{Png, PngError},
+    chunk::{Chunk, ChunkError},
+    chunk_type::{ChunkType, ValidationOptions},
+    clipboard::{Clipboard, SystemClipboard},
+    compression,
+    diff::{self, DiffError},
+    format::{self, OutputFormat},
+    icc::{self, IccProfile},
+    ihdr::Ihdr,
+    input,
+    manifest::Manifest,
+    order::{self, OrderConfig},
+    padding,
+    patch::Patch,
+    png::{Png, PngError, Position},
+    seal, sidecar, size,
+    spec::{self, SpecVersion},
+    stream,
+    text::{self, Charset},
+    verify::{self, Issue, Severity},
 };
 use anyhow::{Error, Result};
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use std::{
+    collections::HashMap,
+    env,
     fs::{self, File},
-    io::{Read, Write},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
@@ -16,6 +36,56 @@ use std::{
 pub struct PngMeArgs {
     #[clap(subcommand)]
     pub command_type: CommandType,
+
+    /// Keep a file with no chunks left after a removal instead of deleting it (or set PNGME_KEEP_EMPTY=1)
+    #[clap(long, global = true, conflicts_with = "delete-empty")]
+    pub keep_empty: bool,
+
+    /// Delete a file with no chunks left after a removal, overriding PNGME_KEEP_EMPTY
+    #[clap(long, global = true)]
+    pub delete_empty: bool,
+
+    /// When a command fails, also print where the PNG file is broken and the bytes found there
+    #[clap(long, global = true)]
+    pub debug: bool,
+}
+
+impl PngMeArgs {
+    const KEEP_EMPTY_VARIABLE: &'static str = "PNGME_KEEP_EMPTY";
+
+    pub fn debug_path(&self) -> Option<String> {
+        self.debug
+            .then(|| self.command_type.file_path())
+            .flatten()
+            .map(String::from)
+    }
+
+    pub fn configured_command(self) -> CommandType {
+        // the global options are applied to the commands they affect before running them
+        let keep_empty = Self::resolve_keep_empty(
+            self.keep_empty,
+            self.delete_empty,
+            env::var(Self::KEEP_EMPTY_VARIABLE).ok(),
+        );
+
+        match self.command_type {
+            CommandType::Remove(remove_args) => CommandType::Remove(RemoveArgs {
+                keep_empty,
+                ..remove_args
+            }),
+            command_type => command_type,
+        }
+    }
+
+    fn resolve_keep_empty(keep_empty: bool, delete_empty: bool, variable: Option<String>) -> bool {
+        // the flags take precedence over the environment variable, and by default files are deleted
+        match (keep_empty, delete_empty, variable.as_deref()) {
+            (true, _, _) => true,
+            (_, true, _) => false,
+            (_, _, Some(value)) => !matches!(value, "" | "0" | "false"),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -31,9 +101,148 @@ pub enum CommandType {
 
     /// Print the chunks of a PNG file
     Print(PrintArgs),
+
+    /// Replace the data of a PNG chunk with the contents of a file
+    ReplaceFrom(ReplaceFromArgs),
+
+    /// Verify the structure and the chunk types of a PNG file
+    Verify(VerifyArgs),
+
+    /// Embed raw EXIF data in the eXIf chunk of a PNG file, replacing any existing one
+    SetExif(SetExifArgs),
+
+    /// Extract the raw EXIF data from the eXIf chunk of a PNG file
+    GetExif(GetExifArgs),
+
+    /// Cyclically shift the ancillary chunks of a PNG file, leaving the critical ones in place
+    Rotate(RotateArgs),
+
+    /// Strip a PNG file down to the chunks needed to render the image
+    Minimize(MinimizeArgs),
+
+    /// Report how many bytes removing each chunk of a PNG file would save
+    SizeImpact(SizeImpactArgs),
+
+    /// Export the metadata of the chunks of a PNG file as CSV
+    Csv(CsvArgs),
+
+    /// List the chunks of a PNG file whose CRC matches the given one
+    FindCrc(FindCrcArgs),
+
+    /// Fix the CRCs and restore the canonical chunk order of a PNG file
+    Touch(TouchArgs),
+
+    /// Compare two PNG files byte by byte, or by their chunks regardless of their order
+    Equal(EqualArgs),
+
+    /// List the chunk types appearing more than once in a PNG file
+    Duplicates(DuplicatesArgs),
+
+    /// Store a SHA-256 hash of all the other chunks of a PNG file in a cKsm chunk
+    Seal(SealArgs),
+
+    /// Check that the chunks of a sealed PNG file still match the hash in its cKsm chunk
+    CheckSeal(CheckSealArgs),
+
+    /// List the chunks of a PNG file in the order a streaming decoder meets them, flagging late ones
+    StreamOrder(StreamOrderArgs),
+
+    /// Print the keyword and the decoded text of every tEXt, zTXt and iTXt chunk of a PNG file
+    Text(TextArgs),
+
+    /// Print the bytes the CRC of a chunk is calculated on, with the calculated and stored CRCs
+    CrcDebug(CrcDebugArgs),
+
+    /// Rewrite a PNG file up to its first IEND, discarding any chunks or bytes after it
+    Clean(CleanArgs),
+
+    /// List the types of the chunks of a PNG file, in order
+    List(ListArgs),
+
+    /// Print a JSON manifest with the type and the base64 data of every chunk of a PNG file
+    Manifest(ManifestArgs),
+
+    /// Build a PNG file from a JSON manifest
+    Build(BuildArgs),
+
+    /// Report the color type, the bit depth and the number of channels from the IHDR of a PNG file
+    Color(ColorArgs),
+
+    /// Show the chunks that changed in a PNG file since its <FILE_PATH>.bak backup
+    DiffBackup(DiffBackupArgs),
+
+    /// Check the signature of a PNG file and the CRC of each of its chunks, without modifying it
+    Validate(ValidateArgs),
+
+    /// Rewrite a PNG file so that its chunks follow the order listed in a .pngme-order.toml file
+    Reorder(ReorderArgs),
+
+    /// Sum the bytes hidden after IEND or in custom chunks of the PNG files in a directory
+    CapacityReport(CapacityReportArgs),
+
+    /// Save the raw data of a chunk of a PNG file, for binary messages that can't be decoded as text
+    Extract(ExtractArgs),
+
+    /// Extract the ICC color profile from the iCCP chunk of a PNG file
+    GetIcc(GetIccArgs),
+
+    /// Embed an ICC color profile into the iCCP chunk of a PNG file
+    SetIcc(SetIccArgs),
+
+    /// Print the completion script for the given shell
+    Completions(CompletionsArgs),
+
+    /// Check that the image of a PNG file can still be decoded
+    #[cfg(feature = "decode-check")]
+    DecodeCheck(DecodeCheckArgs),
 }
 
-#[derive(Debug, Args)]
+impl CommandType {
+    pub fn file_path(&self) -> Option<&str> {
+        let file_path = match self {
+            Self::Encode(args) => &args.file_path,
+            Self::Decode(args) => &args.file_path,
+            Self::Remove(args) => &args.file_path,
+            Self::Print(args) => &args.file_path,
+            Self::ReplaceFrom(args) => &args.file_path,
+            Self::Verify(args) => &args.file_path,
+            Self::SetExif(args) => &args.file_path,
+            Self::GetExif(args) => &args.file_path,
+            Self::Rotate(args) => &args.file_path,
+            Self::Minimize(args) => &args.file_path,
+            Self::SizeImpact(args) => &args.file_path,
+            Self::Csv(args) => &args.file_path,
+            Self::FindCrc(args) => &args.file_path,
+            Self::Touch(args) => &args.file_path,
+            Self::Equal(args) => &args.first_path,
+            Self::Duplicates(args) => &args.file_path,
+            Self::Seal(args) => &args.file_path,
+            Self::CheckSeal(args) => &args.file_path,
+            Self::StreamOrder(args) => &args.file_path,
+            Self::Text(args) => &args.file_path,
+            Self::CrcDebug(args) => &args.file_path,
+            Self::Clean(args) => &args.file_path,
+            Self::List(args) => &args.file_path,
+            Self::Manifest(args) => &args.file_path,
+            Self::Build(_) => return None,
+            Self::Color(args) => &args.file_path,
+            Self::DiffBackup(args) => &args.file_path,
+            Self::Validate(args) => &args.file_path,
+            Self::Reorder(args) => &args.file_path,
+            Self::CapacityReport(_) => return None,
+            Self::Extract(args) => &args.file_path,
+            Self::GetIcc(args) => &args.file_path,
+            Self::SetIcc(args) => &args.file_path,
+            Self::Completions(_) => return None,
+            #[cfg(feature = "decode-check")]
+            Self::DecodeCheck(args) => &args.file_path,
+        };
+
+        Some(file_path)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
 pub struct EncodeArgs {
     /// The path of the PNG file
     pub file_path: String,
@@ -42,19 +251,118 @@ pub struct EncodeArgs {
     pub chunk_type: String,
 
     /// The message to encode
-    pub message: String,
+    #[clap(required_unless_present_any = &["message-file", "from-clipboard"])]
+    pub message: Option<String>,
 
     /// The optional path in which to save the resulting PNG file
     pub output_file: Option<String>,
+
+    /// Do not warn when the resulting file lacks the chunks needed to be viewed as an image
+    #[clap(long)]
+    pub no_warn: bool,
+
+    /// Leave the PNG file untouched and save the inserted bytes as a patch in the given path
+    #[clap(long, conflicts_with = "output-file")]
+    pub emit_patch: Option<String>,
+
+    /// Insert the chunk before-idat, after-idat, before-iend or at the end of a valid PNG file
+    #[clap(long)]
+    pub position: Option<Position>,
+
+    /// Insert the chunk at the given index of a valid PNG file, or at the end if it's past the last chunk
+    #[clap(long, conflicts_with = "position")]
+    pub index: Option<usize>,
+
+    /// Insert the chunk before the IEND of a valid PNG file without loading it all in memory
+    #[clap(long, conflicts_with_all = &["output-file", "emit-patch", "position", "index"])]
+    pub stream: bool,
+
+    /// Store the given value in the length field of the chunk instead of the size of the message
+    #[clap(long)]
+    pub force_length: Option<u32>,
+
+    /// Reject messages that are not Latin-1 when encoding them in a tEXt or zTXt chunk
+    #[clap(long, conflicts_with = "message-file")]
+    pub strict_text: bool,
+
+    /// Accept a chunk type whose reserved bit is set (lowercase third letter)
+    #[clap(long)]
+    pub allow_reserved_bit_set: bool,
+
+    /// Only modify the file if its SHA-256 hash, in hex, matches this one
+    #[clap(long, conflicts_with = "stream")]
+    pub expect_hash: Option<String>,
+
+    /// Leave the file unchanged if it already contains a chunk with the same type and message,
+    /// exiting with code 3 instead of 0
+    #[clap(long, conflicts_with = "output-file")]
+    pub append_if_missing: bool,
+
+    /// Prefix the message with its length and pad it with zeros to exactly this many bytes
+    #[clap(long)]
+    pub pad_to: Option<usize>,
+
+    /// Encode the bytes of this file, as they are, instead of the message
+    #[clap(long, conflicts_with = "message")]
+    pub message_file: Option<String>,
+
+    /// Replace the data of the first chunk with the same type instead of adding a duplicate
+    #[clap(long, conflicts_with_all = &["stream", "emit-patch", "force-length"])]
+    pub update: bool,
+
+    /// Encode the text currently in the clipboard instead of the message
+    #[clap(long, conflicts_with_all = &["message", "message-file"])]
+    pub from_clipboard: bool,
+
+    /// Add the chunk even if the file already contains one with the same type
+    #[clap(long, conflicts_with = "update")]
+    pub allow_duplicate: bool,
 }
 
 #[derive(Debug, Args)]
 pub struct DecodeArgs {
-    /// The path of the PNG file
+    /// The path of the PNG file, or "-" to read it from the standard input
     pub file_path: String,
 
     /// The type of PNG chunk to decode
-    pub chunk_type: String,
+    #[clap(required_unless_present = "auto")]
+    pub chunk_type: Option<String>,
+
+    /// Decode the only custom chunk of the file, without specifying its type
+    #[clap(long, conflicts_with = "chunk-type")]
+    pub auto: bool,
+
+    /// The charset of the message: utf8, latin1 (as defined for tEXt chunks), auto, utf16le or utf16be
+    #[clap(long, default_value_t)]
+    pub charset: Charset,
+
+    /// Strip the padding added by encode --pad-to, keeping only the message before it
+    #[clap(long)]
+    pub unpad: bool,
+
+    /// Decode the message as UTF-16 little-endian, the same as --charset utf16le
+    #[clap(long, conflicts_with = "utf16be")]
+    pub utf16le: bool,
+
+    /// Decode the message as UTF-16 big-endian, the same as --charset utf16be
+    #[clap(long)]
+    pub utf16be: bool,
+
+    /// Prefix the message with the type and the length of its chunk, as in "tEXt(11): message"
+    #[clap(long)]
+    pub with_meta: bool,
+
+    /// Concatenate the messages of every chunk of the given type, instead of only the first one
+    #[clap(long, requires = "chunk-type")]
+    pub all: bool,
+
+    /// Prefix the message with the zero-based index of its chunk in the file, as in "Chunk 2: message"
+    #[clap(long)]
+    pub show_index: bool,
+
+    /// Copy the decoded message to the clipboard instead of printing it
+    #[clap(long)]
+    pub to_clipboard: bool,
 }
 
 #[derive(Debug, Args)]
@@ -64,518 +372,5493 @@ pub struct RemoveArgs {
 
     /// The type of PNG chunk to remove
     pub chunk_type: String,
+
+    /// Copy the file chunk by chunk instead of loading it in memory, useful for large files
+    #[clap(long)]
+    pub stream: bool,
+
+    /// Remove every chunk of the given type instead of only the last one
+    #[clap(long, conflicts_with = "stream")]
+    pub all: bool,
+
+    /// Only report whether the removal would leave the image decodable, without removing anything
+    #[clap(long, conflicts_with = "stream")]
+    pub check: bool,
+
+    /// Whether a file with no chunks left is kept, set from the global options
+    #[clap(skip)]
+    pub keep_empty: bool,
 }
 
 #[derive(Debug, Args)]
 pub struct PrintArgs {
     /// The path of the PNG file
     pub file_path: String,
+
+    /// Print sizes in human-readable units (KiB, MiB, GiB) instead of bytes
+    #[clap(long)]
+    pub human: bool,
+
+    /// Annotate the chunk types with the descriptions found in the <FILE_PATH>.meta JSON sidecar
+    #[clap(long)]
+    pub annotate: bool,
+
+    /// Report whether the data of each chunk looks zlib-compressed or raw, and the compression
+    /// method declared by zTXt, iCCP and iTXt chunks
+    #[clap(long)]
+    pub detect_compression: bool,
+
+    /// Report whether each chunk is critical, public, has a valid reserved bit and is safe to copy
+    #[clap(long)]
+    pub verbose: bool,
+
+    /// Group the chunks by image data, animation frames and metadata instead of listing them
+    #[clap(long)]
+    pub tree: bool,
+
+    /// The position in the file where the PNG starts, for PNGs embedded inside other files
+    #[clap(long, default_value_t = 0)]
+    pub offset: usize,
+
+    /// The number of bytes taken by the embedded PNG, by default up to the end of the file
+    #[clap(long)]
+    pub length: Option<usize>,
+
+    /// The output format: text, or json for an array with the length, type, data_len and crc of each chunk
+    #[clap(long, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Only list the length and type of each chunk, seeking past their data without checking it
+    #[clap(
+        long,
+        conflicts_with_all = &["annotate", "detect-compression", "verbose", "tree", "length", "format"]
+    )]
+    pub headers_only: bool,
 }
 
-enum FileState {
-    Png,
-    Empty,
-    Other(Error),
+#[derive(Debug, Args)]
+pub struct ReplaceFromArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The type of PNG chunk whose data will be replaced
+    pub chunk_type: String,
+
+    /// The path of the file containing the new data
+    pub data_path: String,
 }
 
-impl EncodeArgs {
-    pub fn encode(&self) -> Result<()> {
-        let mut input_file = File::options()
-            .read(true)
-            .append(true)
-            .create(true)
-            .open(&self.file_path)?;
-        let chunk = Chunk::new(
-            ChunkType::from_str(&self.chunk_type)?,
-            self.message.as_bytes().to_vec(),
-        );
-        let mut input_buffer = Vec::<u8>::new();
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// The path of the PNG file
+    pub file_path: String,
 
-        input_file.read_to_end(&mut input_buffer)?;
+    /// The version of the PNG specification to verify against ("1.2" or "3")
+    #[clap(long, default_value_t)]
+    pub spec: SpecVersion,
 
-        if let Some(output_path) = &self.output_file {
-            // fill buffer according to both input and output
-            let mut output_file = File::options()
-                .read(true)
-                .write(true)
-                .create(true)
-                .open(output_path)?;
-            let mut output_buffer = Vec::<u8>::new();
+    /// Treat warnings as errors, failing the verification when any issue is found
+    #[clap(long)]
+    pub werror: bool,
 
-            output_file.read_to_end(&mut output_buffer)?;
-            output_file
-                .write_all(&Self::validate_input_with_output(
-                    &input_buffer,
-                    &output_buffer,
-                    chunk,
-                )?)
-                .map_err(|e| e.into())
-        } else {
-            // fill buffer only according to input
-            input_file
-                .write_all(&Self::validate_input(&input_buffer, chunk)?)
-                .map_err(|e| e.into())
-        }
-    }
+    /// Print the issues as a JSON object with an overall "ok" and the list of "issues"
+    #[clap(long)]
+    pub json: bool,
+}
 
-    fn validate_png(input_contents: &Vec<u8>) -> FileState {
-        if input_contents.is_empty() {
-            FileState::Empty
-        } else {
-            match Png::try_from(&input_contents[..]) {
-                Ok(_) => FileState::Png,
-                Err(e) => FileState::Other(Error::from(e)),
-            }
-        }
-    }
+#[derive(Debug, Args)]
+pub struct SetExifArgs {
+    /// The path of the PNG file
+    pub file_path: String,
 
-    fn validate_input_with_output(
-        input_buffer: &Vec<u8>,
-        output_buffer: &Vec<u8>,
-        chunk: Chunk,
-    ) -> Result<Vec<u8>> {
-        match (
-            Self::validate_png(input_buffer),
-            Self::validate_png(output_buffer),
-        ) {
-            (FileState::Png, FileState::Empty) => {
-                // valid input, empty output
-                let mut png = Png::try_from(&input_buffer[..])?;
+    /// The path of the file containing the raw EXIF data
+    pub exif_path: String,
+}
 
-                png.append_chunk(chunk);
-                Ok(png.as_bytes())
-            }
-            (FileState::Empty, FileState::Empty) => {
-                // empty input, empty output
-                Ok(Png::from_chunks(vec![chunk]).as_bytes())
-            }
-            (FileState::Png, FileState::Png) => todo!(), // valid input, valid output
-            (FileState::Empty, FileState::Png) => todo!(), // empty input, valid output
-            (FileState::Other(e), _) | (_, FileState::Other(e)) => Err(e), // invalid input or output
+#[derive(Debug, Args)]
+pub struct GetExifArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The path in which to save the raw EXIF data
+    pub output_path: String,
+
+    /// Save the EXIF data encoded in base64 instead of raw
+    #[clap(long)]
+    pub base64: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RotateArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The number of positions to shift the chunks by, negative values shift towards the start
+    #[clap(allow_hyphen_values = true)]
+    pub positions: isize,
+}
+
+#[derive(Debug, Args)]
+pub struct MinimizeArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The optional path in which to save the resulting PNG file, instead of overwriting the input
+    #[clap(short, long)]
+    pub output_file: Option<String>,
+
+    /// The compression level, from 0 (fastest) to 9 (smallest), used when writing a .png.gz file
+    #[clap(long, default_value_t = compression::DEFAULT_GZIP_LEVEL, parse(try_from_str = parse_gzip_level))]
+    pub gzip_level: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct SizeImpactArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CsvArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct FindCrcArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The CRC to look for, either in decimal or in hexadecimal with the 0x prefix
+    #[clap(parse(try_from_str = parse_crc))]
+    pub crc: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct TouchArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct EqualArgs {
+    /// The path of the first PNG file
+    pub first_path: String,
+
+    /// The path of the second PNG file
+    pub second_path: String,
+
+    /// Consider the files equal when they contain the same chunks in any order
+    #[clap(long)]
+    pub ignore_order: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct DuplicatesArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SealArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckSealArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct StreamOrderArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct TextArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CrcDebugArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The type of PNG chunk to debug
+    pub chunk_type: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CleanArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ManifestArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct BuildArgs {
+    /// The path of the JSON manifest
+    pub manifest_path: String,
+
+    /// The path in which to save the resulting PNG file
+    #[clap(short, long)]
+    pub output_file: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ColorArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffBackupArgs {
+    /// The path of the PNG file, whose backup is expected at <FILE_PATH>.bak
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CapacityReportArgs {
+    /// The path of the directory containing the PNG files
+    pub dir_path: String,
+
+    /// Also look for PNG files in the subdirectories
+    #[clap(long)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExtractArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The type of PNG chunk to extract
+    pub chunk_type: String,
+
+    /// The path in which to save the raw data of the chunk
+    pub output_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct GetIccArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The path in which to save the decompressed ICC profile
+    pub output_path: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SetIccArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The path of the ICC profile to embed
+    pub profile_path: String,
+
+    /// The name of the profile, 1 to 79 Latin-1 characters
+    #[clap(long)]
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ReorderArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+
+    /// The path of the TOML file with the order of the chunks
+    #[clap(long, default_value = order::CONFIG_FILE_NAME)]
+    pub config: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CompletionsArgs {
+    /// The shell for which to generate the completion script
+    #[clap(arg_enum)]
+    pub shell: Shell,
+}
+
+#[cfg(feature = "decode-check")]
+#[derive(Debug, Args)]
+pub struct DecodeCheckArgs {
+    /// The path of the PNG file
+    pub file_path: String,
+}
+
+enum FileState {
+    Png,
+    Empty,
+    Other(Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncodeError {
+    #[error("There is nothing to encode, a message or a message file is required")]
+    MissingMessage,
+    #[error("--strict-text only applies to a message, not to the bytes of a message file")]
+    StrictTextFromFile,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeOutcome {
+    /// The chunk was written, possibly with a warning about the resulting file
+    Modified(Option<String>),
+    /// The file already contained the chunk, or updating it wouldn't change a byte, so nothing was written
+    Unchanged,
+}
+
+impl EncodeOutcome {
+    pub fn warning(&self) -> Option<&str> {
+        match self {
+            Self::Modified(warning) => warning.as_deref(),
+            Self::Unchanged => None,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        // scripts can tell an idempotent run apart from both a success and an error, while clap
+        // already uses 2 for invalid arguments
+        match self {
+            Self::Modified(_) => 0,
+            Self::Unchanged => 3,
+        }
+    }
+}
+
+impl EncodeArgs {
+    pub fn encode(&self) -> Result<EncodeOutcome> {
+        if self.from_clipboard {
+            return self.encode_from_clipboard(&mut SystemClipboard::new()?);
+        }
+
+        if (self.append_if_missing && self.contains_chunk()?) || self.update_is_noop()? {
+            return Ok(EncodeOutcome::Unchanged);
+        }
+
+        // decode only ever finds the first chunk of a type, so a second one would go unnoticed
+        if !self.allow_duplicate && !self.update && self.contains_type() {
+            return Err(PngError::DuplicateChunk(self.chunk_type.clone()).into());
+        }
+
+        self.write_chunk().map(EncodeOutcome::Modified)
+    }
+
+    pub fn encode_from_clipboard(&self, clipboard: &mut impl Clipboard) -> Result<EncodeOutcome> {
+        EncodeArgs {
+            message: Some(clipboard.get_text()?),
+            from_clipboard: false,
+            ..self.clone()
+        }
+        .encode()
+    }
+
+    fn contains_chunk(&self) -> Result<bool> {
+        // a missing or invalid file can't contain the chunk, the usual checks report it later
+        let chunk = self.chunk()?;
+
+        Ok(fs::read(&self.file_path)
+            .ok()
+            .and_then(|buffer| Png::try_from(&buffer[..]).ok())
+            .is_some_and(|png| png.chunks().contains(&chunk)))
+    }
+
+    fn update_is_noop(&self) -> Result<bool> {
+        // replacing a chunk with an identical one would write back the very same bytes
+        if !self.update || self.output_file.is_some() {
+            return Ok(false);
+        }
+
+        let chunk = self.chunk()?;
+
+        Ok(fs::read(&self.file_path)
+            .ok()
+            .and_then(|buffer| Png::try_from(&buffer[..]).ok())
+            .is_some_and(|png| png.chunk_by_type(&self.chunk_type) == Some(&chunk)))
+    }
+
+    fn contains_type(&self) -> bool {
+        // only the chunk headers are read, so this works with --stream on files of any size
+        File::open(self.target_path())
+            .ok()
+            .and_then(|f| stream::find_chunk_data(&mut BufReader::new(f), &self.chunk_type).ok())
+            .flatten()
+            .is_some()
+    }
+
+    fn target_path(&self) -> &str {
+        // appending to an output that already holds a PNG adds to its chunks, anything else starts
+        // from the chunks of the input
+        match &self.output_file {
+            Some(output_path)
+                if self.position.is_none()
+                    && self.index.is_none()
+                    && fs::metadata(output_path).is_ok_and(|m| m.len() > 0) =>
+            {
+                output_path
+            }
+            _ => &self.file_path,
+        }
+    }
+
+    fn write_chunk(&self) -> Result<Option<String>> {
+        if self.stream {
+            return self.encode_streaming();
+        }
+
+        let chunk = self.chunk()?;
+        let input_buffer = match fs::read(&self.file_path) {
+            Ok(buffer) => buffer,
+            // a missing file is created by the encoding, unless only a patch is wanted
+            Err(e) if e.kind() == ErrorKind::NotFound && self.emit_patch.is_none() => vec![],
+            Err(e) => return Err(e.into()),
+        };
+
+        // the file might have changed since the caller looked at it, in that case it's left alone
+        if let Some(expected_hash) = &self.expect_hash {
+            seal::check_hash(&input_buffer, expected_hash)?;
+        }
+
+        // without a chunk to update, the new one is added as usual
+        if let Some(index) = self.update_index(&input_buffer) {
+            return self.encode_at(&input_buffer, chunk, |png, chunk| {
+                png.replace_chunk(&self.chunk_type, chunk.data().to_vec())?;
+                Ok(index)
+            });
+        }
+
+        if let Some(position) = self.position {
+            return self.encode_at(&input_buffer, chunk, |png, chunk| {
+                png.insert_at_position(chunk, position)
+            });
+        }
+
+        if let Some(index) = self.index {
+            return self.encode_at(&input_buffer, chunk, |png, chunk| {
+                Ok(png.insert_chunk(index, chunk))
+            });
+        }
+
+        if let Some(patch_path) = &self.emit_patch {
+            // the new bytes always end up at the end of the input file, which is left untouched
+            let new_bytes = Self::validate_input(&input_buffer, chunk)?;
+
+            fs::write(
+                patch_path,
+                Patch::new(input_buffer.len() as u64, new_bytes.clone()).as_bytes(),
+            )?;
+
+            return Ok(self.warning(&[input_buffer, new_bytes].concat()));
+        }
+
+        let (mut file, mut contents, new_bytes) = if let Some(output_path) = &self.output_file {
+            // fill buffer according to both input and output
+            let mut output_file = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(output_path)?;
+            let mut output_buffer = Vec::<u8>::new();
+
+            output_file.read_to_end(&mut output_buffer)?;
+
+            let new_bytes = Self::validate_input_with_output(&input_buffer, &output_buffer, chunk)?;
+
+            (output_file, output_buffer, new_bytes)
+        } else {
+            // fill buffer only according to input
+            let new_bytes = Self::validate_input(&input_buffer, chunk)?;
+            let input_file = File::options()
+                .append(true)
+                .create(true)
+                .open(&self.file_path)?;
+
+            (input_file, input_buffer, new_bytes)
+        };
+
+        file.write_all(&new_bytes)?;
+        contents.extend(new_bytes);
+
+        Ok(self.warning(&contents))
+    }
+
+    fn update_index(&self, input_buffer: &[u8]) -> Option<usize> {
+        if !self.update {
+            return None;
+        }
+
+        Png::try_from(input_buffer)
+            .ok()?
+            .chunks()
+            .iter()
+            .position(|c| c.chunk_type().to_string() == self.chunk_type)
+    }
+
+    fn chunk(&self) -> Result<Chunk> {
+        let chunk_type = ChunkType::from_str(&self.chunk_type)?;
+
+        chunk_type.validate(&ValidationOptions {
+            allow_reserved_bit_set: self.allow_reserved_bit_set,
+        })?;
+
+        let mut data = match (&self.message, &self.message_file) {
+            (Some(message), _) if self.strict_text && text::is_latin1_chunk(&self.chunk_type) => {
+                text::encode_latin1(message)?
+            }
+            (Some(message), _) => message.as_bytes().to_vec(),
+            (None, Some(_)) if self.strict_text => {
+                return Err(EncodeError::StrictTextFromFile.into())
+            }
+            (None, Some(path)) => fs::read(path)?,
+            // clap requires one of them, so this only happens when the struct is built by hand
+            (None, None) => return Err(EncodeError::MissingMessage.into()),
+        };
+
+        if let Some(length) = self.pad_to {
+            data = padding::pad(&data, length)?;
+        }
+
+        Ok(match self.force_length {
+            // a length that disagrees with the data is only useful to test how decoders react to it
+            Some(length) => Chunk::from_parts(length, chunk_type, data),
+            None => Chunk::new(chunk_type, data),
+        })
+    }
+
+    fn encode_at(
+        &self,
+        input_buffer: &[u8],
+        chunk: Chunk,
+        insert: impl FnOnce(&mut Png, Chunk) -> Result<usize>,
+    ) -> Result<Option<String>> {
+        // inserting in the middle requires a valid PNG file, which is then written as a whole
+        let mut png = Png::try_from(input_buffer)?;
+        let chunk_bytes = chunk.as_bytes();
+        let index = insert(&mut png, chunk)?;
+
+        match &self.emit_patch {
+            // the chunk starts with its length and type, 8 bytes before its data
+            Some(patch_path) => fs::write(
+                patch_path,
+                Patch::new(png.data_offset_of(index).unwrap() as u64 - 8, chunk_bytes).as_bytes(),
+            )?,
+            None => {
+                let output_path = self.output_file.as_ref().unwrap_or(&self.file_path);
+                let mut writer = BufWriter::new(File::create(output_path)?);
+
+                png.write_to(&mut writer)?;
+                writer.flush()?;
+            }
+        }
+
+        Ok(self.png_warning(&png))
+    }
+
+    fn encode_streaming(&self) -> Result<Option<String>> {
+        // only the chunk headers and whatever follows IEND are read, the rest is skipped over
+        let chunk = self.chunk()?;
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .open(&self.file_path)?;
+        let chunk_types = stream::insert_before_end(&mut file, &chunk)?;
+
+        Ok(self.warning_for_types(&chunk_types))
+    }
+
+    fn warning(&self, contents: &[u8]) -> Option<String> {
+        let png = Png::try_from(contents).ok()?;
+
+        self.png_warning(&png)
+    }
+
+    fn png_warning(&self, png: &Png) -> Option<String> {
+        let chunk_types = png
+            .chunks()
+            .iter()
+            .map(|c| c.chunk_type().to_string())
+            .collect::<Vec<String>>();
+
+        self.warning_for_types(&chunk_types)
+    }
+
+    fn warning_for_types(&self, chunk_types: &[String]) -> Option<String> {
+        // a file without IHDR, IDAT and IEND is still readable by this tool but not by image viewers
+        if self.no_warn {
+            return None;
+        }
+
+        let missing_chunks = ["IHDR", "IDAT", "IEND"]
+            .into_iter()
+            .filter(|t| !chunk_types.iter().any(|c| c == t))
+            .collect::<Vec<&str>>();
+
+        if missing_chunks.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "the resulting file is missing the {} chunks, so it may not be viewable as an image",
+                missing_chunks.join(", ")
+            ))
+        }
+    }
+
+    fn validate_png(input_contents: &[u8]) -> FileState {
+        if input_contents.is_empty() {
+            FileState::Empty
+        } else {
+            match Png::try_from(input_contents) {
+                Ok(_) => FileState::Png,
+                Err(e) => FileState::Other(Error::from(e)),
+            }
+        }
+    }
+
+    fn validate_input_with_output(
+        input_buffer: &[u8],
+        output_buffer: &[u8],
+        chunk: Chunk,
+    ) -> Result<Vec<u8>> {
+        match (
+            Self::validate_png(input_buffer),
+            Self::validate_png(output_buffer),
+        ) {
+            (FileState::Png, FileState::Empty) => {
+                // valid input, empty output
+                let mut png = Png::try_from(input_buffer)?;
+
+                png.append_chunk(chunk);
+                Ok(png.as_bytes())
+            }
+            (FileState::Empty, FileState::Empty) => {
+                // empty input, empty output
+                Ok(Png::from_chunks(vec![chunk]).as_bytes())
+            }
+            (FileState::Png, FileState::Png) | (FileState::Empty, FileState::Png) => {
+                // valid or empty input, valid output: the input is only validated
+                let mut png = Png::try_from(output_buffer)?;
+
+                png.append_chunk(chunk);
+
+                // the output file already holds its chunks, so only what follows them is returned
+                Ok(png.as_bytes()[output_buffer.len()..].to_vec())
+            }
+            (FileState::Other(e), _) | (_, FileState::Other(e)) => Err(e), // invalid input or output
+        }
+    }
+
+    fn validate_input(input_buffer: &[u8], chunk: Chunk) -> Result<Vec<u8>> {
+        match Self::validate_png(input_buffer) {
+            FileState::Png => Ok(chunk.as_bytes()), // valid input
+            FileState::Empty => Ok(Png::from_chunks(vec![chunk]).as_bytes()), // empty input
+            FileState::Other(e) => Err(e),          // invalid input
+        }
+    }
+}
+
+fn validate_chunk_type(chunk_type: &str) -> Result<(), ChunkError> {
+    // a malformed type could never match any chunk, so it's reported instead of "chunk not found"
+    ChunkType::from_str(chunk_type)?;
+
+    Ok(())
+}
+
+impl DecodeArgs {
+    pub fn decode(&self) -> Result<String> {
+        match self.file_path.as_str() {
+            "-" => self.decode_from(io::stdin().lock()),
+            file_path => self.decode_from(input::open(file_path)?),
+        }
+    }
+
+    pub fn decode_from<R: Read>(&self, mut reader: R) -> Result<String> {
+        if let Some(chunk_type) = &self.chunk_type {
+            validate_chunk_type(chunk_type)?;
+        }
+
+        let mut buffer = vec![];
+
+        reader.read_to_end(&mut buffer)?;
+
+        let png = input::parse_png(&buffer)?;
+        let chunks = match &self.chunk_type {
+            Some(chunk_type) if self.all => png.indexed_chunks_by_type(chunk_type),
+            Some(chunk_type) => png
+                .indexed_chunks_by_type(chunk_type)
+                .into_iter()
+                .take(1)
+                .collect(),
+            None => match png.indexed_custom_chunks()[..] {
+                [chunk] => vec![chunk],
+                [] => vec![],
+                ref chunks => return Err(PngError::AmbiguousChunkError(chunks.len()).into()),
+            },
+        };
+
+        if chunks.is_empty() {
+            return Err(PngError::ChunkNotFoundError.into());
+        }
+
+        let messages = chunks
+            .iter()
+            .map(|(i, c)| self.decode_chunk(*i, c))
+            .collect::<Result<Vec<String>>>()?;
+
+        // with a prefix every message is on its own line, otherwise they're joined back together
+        Ok(messages.join(if self.with_meta || self.show_index {
+            "\n"
+        } else {
+            ""
+        }))
+    }
+
+    pub fn decode_to_clipboard(&self, clipboard: &mut impl Clipboard) -> Result<String> {
+        let message = self.decode()?;
+
+        clipboard.set_text(&message)?;
+
+        Ok(message)
+    }
+
+    fn charset(&self) -> Charset {
+        match (self.utf16le, self.utf16be) {
+            (true, _) => Charset::Utf16Le,
+            (_, true) => Charset::Utf16Be,
+            _ => self.charset,
+        }
+    }
+
+    fn decode_chunk(&self, index: usize, chunk: &Chunk) -> Result<String> {
+        let data = if self.unpad {
+            chunk.read_length_prefixed()?.0
+        } else {
+            chunk.data()
+        };
+        let mut message = self.charset().decode(data)?;
+
+        if self.with_meta {
+            message = format!("{}({}): {message}", chunk.chunk_type(), chunk.length());
+        }
+
+        if self.show_index {
+            message = format!("Chunk {index}: {message}");
+        }
+
+        Ok(message)
+    }
+}
+
+impl RemoveArgs {
+    pub fn remove(&self) -> Result<Chunk> {
+        validate_chunk_type(&self.chunk_type)?;
+
+        if self.stream {
+            return self.remove_streaming();
+        }
+
+        let buffer = input::read(&self.file_path)?;
+        let mut png = input::parse_png(&buffer)?;
+        let removed_chunk = png.remove_chunk(&self.chunk_type);
+
+        self.save(&png, removed_chunk.is_ok());
+
+        removed_chunk
+    }
+
+    pub fn remove_all(&self) -> Result<usize> {
+        validate_chunk_type(&self.chunk_type)?;
+
+        let buffer = input::read(&self.file_path)?;
+        let mut png = input::parse_png(&buffer)?;
+        let mut removed_chunks = 0;
+
+        // the first failure is only an error when not even one chunk could be removed
+        loop {
+            match png.remove_chunk(&self.chunk_type) {
+                Ok(_) => removed_chunks += 1,
+                Err(e) if removed_chunks == 0 => return Err(e),
+                Err(_) => break,
+            }
+        }
+
+        self.save(&png, true);
+
+        Ok(removed_chunks)
+    }
+
+    pub fn check(&self) -> Result<Option<&'static str>> {
+        validate_chunk_type(&self.chunk_type)?;
+
+        let buffer = input::read(&self.file_path)?;
+
+        Ok(self.unsafe_reason(&input::parse_png(&buffer)?))
+    }
+
+    fn unsafe_reason(&self, png: &Png) -> Option<&'static str> {
+        // with --all even several IDAT chunks would all go away
+        if self.all && self.chunk_type == "IDAT" && png.chunk_by_type("IDAT").is_some() {
+            return Some("it would remove every IDAT chunk, holding the image data");
+        }
+
+        png.can_remove_type(&self.chunk_type).err()
+    }
+
+    fn save(&self, png: &Png, changed: bool) {
+        if png.chunks().is_empty() && !self.keep_empty {
+            fs::remove_file(&self.file_path).unwrap();
+        } else if changed {
+            fs::write(&self.file_path, &png.as_bytes()[..]).unwrap();
+        }
+    }
+
+    fn remove_streaming(&self) -> Result<Chunk> {
+        // the output goes to a temporary file which replaces the input only if everything succeeded
+        let temp_path = format!("{}.tmp", self.file_path);
+        let mut input_file = BufReader::new(input::open(&self.file_path)?);
+        let mut temp_file = BufWriter::new(File::create(&temp_path)?);
+        let result = stream::remove_chunk(&mut input_file, &mut temp_file, &self.chunk_type)
+            .and_then(|r| {
+                temp_file.flush()?;
+                Ok(r)
+            });
+
+        drop(temp_file);
+
+        match result {
+            Ok((removed_chunk, 0)) if !self.keep_empty => {
+                fs::remove_file(&temp_path)?;
+                fs::remove_file(&self.file_path)?;
+                Ok(removed_chunk)
+            }
+            Ok((removed_chunk, _)) => {
+                fs::rename(&temp_path, &self.file_path)?;
+                Ok(removed_chunk)
+            }
+            Err(e) => {
+                fs::remove_file(&temp_path)?;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl PrintArgs {
+    pub fn print(&self) -> Result<String> {
+        if self.headers_only {
+            return self.print_headers();
+        }
+
+        let buffer = input::read(&self.file_path)?;
+        let png = input::parse_png(Self::embedded_png(&buffer, self.offset, self.length)?)?;
+
+        if self.format == OutputFormat::Json {
+            return format::chunks_json(&png);
+        }
+
+        let mut output = if png.chunks().is_empty() {
+            // a file made of just the PNG signature is valid, but there's nothing to list
+            String::from("PNG with 0 chunks (header-only file)\n")
+        } else if self.tree {
+            Self::tree(&png, self.human)
+        } else if self.human {
+            format!("{png:#}")
+        } else {
+            png.to_string()
+        };
+
+        if let Ok(ihdr) = Ihdr::from_png(&png) {
+            output.push_str(&format!("Image: {ihdr}\n"));
+        }
+
+        if self.annotate {
+            output.push_str(&Self::annotations(&png, &self.file_path)?);
+        }
+
+        if self.detect_compression {
+            output.push_str(&Self::compression_report(&png));
+        }
+
+        if self.verbose {
+            output.push_str(&Self::properties_report(&png));
+        }
+
+        Ok(output)
+    }
+
+    fn print_headers(&self) -> Result<String> {
+        let mut reader = BufReader::new(input::open(&self.file_path)?);
+
+        reader.seek(SeekFrom::Start(self.offset as u64))?;
+
+        let headers = stream::read_chunk_headers(&mut reader)?;
+        let mut output = format!("PNG with {} chunks\n", headers.len());
+
+        // the same layout as the Display of Chunk, without the data and crc which are never read
+        for (length, chunk_type) in headers {
+            let length = if self.human {
+                size::human_readable(length as u64)
+            } else {
+                length.to_string()
+            };
+
+            output.push_str(&format!(
+                "Chunk {{\n  Length: {length}\n  Type: {chunk_type}\n}}\n"
+            ));
+        }
+
+        Ok(output)
+    }
+
+    fn embedded_png(
+        buffer: &[u8],
+        offset: usize,
+        length: Option<usize>,
+    ) -> Result<&[u8], PngError> {
+        let end = match length {
+            Some(length) => offset.checked_add(length),
+            None => Some(buffer.len()),
+        };
+
+        end.filter(|&end| offset <= end && end <= buffer.len())
+            .map(|end| &buffer[offset..end])
+            .ok_or(PngError::RangeOutOfBounds(offset, length, buffer.len()))
+    }
+
+    fn tree(png: &Png, human: bool) -> String {
+        /*
+            from https://wiki.mozilla.org/APNG_Specification
+
+            every fcTL chunk starts a new frame, and the fdAT chunks following it hold the data of that frame
+        */
+        let mut image_data = vec![];
+        let mut animation = vec![];
+        let mut metadata = vec![];
+        let mut frame = None;
+        let (mut first, mut last) = (vec![], vec![]);
+
+        for (i, chunk) in png.chunks().iter().enumerate() {
+            let size = if human {
+                size::human_readable(chunk.length() as u64)
+            } else {
+                format!("{} bytes", chunk.length())
+            };
+            let line = format!("Chunk {i}: {} ({size})", chunk.chunk_type());
+
+            match chunk.chunk_type().to_string().as_str() {
+                "IHDR" => first.push(format!("  {line}\n")),
+                "IEND" => last.push(format!("  {line}\n")),
+                "PLTE" | "IDAT" => image_data.push(format!("    {line}\n")),
+                "acTL" => animation.push(format!("    {line}\n")),
+                "fcTL" => {
+                    let number = frame.map_or(0, |n| n + 1);
+
+                    frame = Some(number);
+                    animation.push(format!("    Frame {number}\n      {line}\n"));
+                }
+                "fdAT" if frame.is_some() => animation.push(format!("      {line}\n")),
+                "fdAT" => animation.push(format!("    {line}\n")),
+                _ => metadata.push(format!("    {line}\n")),
+            }
+        }
+
+        let mut tree = format!("PNG with {} chunks\n", png.chunks().len());
+
+        tree.push_str(&first.concat());
+
+        for (name, group) in [
+            ("Image data", image_data),
+            ("Animation", animation),
+            ("Metadata", metadata),
+        ] {
+            if !group.is_empty() {
+                tree.push_str(&format!("  {name}\n{}", group.concat()));
+            }
+        }
+
+        tree.push_str(&last.concat());
+        tree
+    }
+
+    fn compression_report(png: &Png) -> String {
+        let mut report = String::from("Compression:\n");
+
+        for (i, chunk) in png.chunks().iter().enumerate() {
+            let chunk_type = chunk.chunk_type().to_string();
+            let mut kind = match compression::zlib_offset(chunk.data()) {
+                Some(0) => String::from("zlib"),
+                Some(offset) => format!("zlib after {offset} bytes"),
+                None => String::from("raw"),
+            };
+
+            match compression::declared_method(&chunk_type, chunk.data()) {
+                Some(0) => kind.push_str(", method 0 (zlib)"),
+                Some(method) => kind.push_str(&format!(", method {method} (nonstandard)")),
+                None => {}
+            }
+
+            report.push_str(&format!("  Chunk {i} ({chunk_type}): {kind}\n"));
+        }
+
+        report
+    }
+
+    fn properties_report(png: &Png) -> String {
+        let mut report = String::from("Properties:\n");
+
+        for (i, chunk) in png.chunks().iter().enumerate() {
+            let chunk_type = chunk.chunk_type();
+
+            report.push_str(&format!(
+                "  Chunk {i} ({chunk_type}): critical: {}, public: {}, reserved bit valid: {}, safe to copy: {}\n",
+                chunk_type.is_critical(),
+                chunk_type.is_public(),
+                chunk_type.is_reserved_bit_valid(),
+                chunk_type.is_safe_to_copy()
+            ));
+        }
+
+        report
+    }
+
+    fn annotations(png: &Png, file_path: &str) -> Result<String> {
+        // every described type is listed once, in the order in which it first appears
+        let descriptions = sidecar::load_descriptions(file_path)?;
+        let mut described_types = vec![];
+
+        for chunk in png.chunks() {
+            let chunk_type = chunk.chunk_type().to_string();
+
+            if descriptions.contains_key(&chunk_type) && !described_types.contains(&chunk_type) {
+                described_types.push(chunk_type);
+            }
+        }
+
+        if described_types.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut annotations = String::from("Descriptions:\n");
+
+        for chunk_type in described_types {
+            annotations.push_str(&format!("  {chunk_type}: {}\n", descriptions[&chunk_type]));
+        }
+
+        Ok(annotations)
+    }
+}
+
+impl ReplaceFromArgs {
+    pub fn replace_from(&self) -> Result<Chunk> {
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from(&buffer[..])?;
+        let data = fs::read(&self.data_path)?;
+
+        let replaced_chunk = png.replace_chunk(&self.chunk_type, data)?.clone();
+
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok(replaced_chunk)
+    }
+}
+
+impl VerifyArgs {
+    pub fn verify(&self) -> Result<Vec<Issue>> {
+        // a chunk with a bad crc is reported as an issue instead of failing to parse the file
+        let buffer = fs::read(&self.file_path)?;
+
+        Ok(verify::verify(
+            &Png::try_from_unchecked(&buffer[..])?,
+            self.spec,
+        ))
+    }
+
+    pub fn json_report(&self, issues: &[Issue]) -> Result<String> {
+        verify::report_json(issues, self.exit_code(issues) == 0)
+    }
+
+    pub fn exit_code(&self, issues: &[Issue]) -> i32 {
+        let fails = issues
+            .iter()
+            .any(|i| self.werror || i.severity == Severity::Error);
+
+        i32::from(fails)
+    }
+}
+
+const EXIF_CHUNK_TYPE: &str = "eXIf";
+
+impl SetExifArgs {
+    pub fn set_exif(&self) -> Result<Chunk> {
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from(&buffer[..])?;
+        let exif = fs::read(&self.exif_path)?;
+        let exif_chunk = if png.chunk_by_type(EXIF_CHUNK_TYPE).is_some() {
+            png.replace_chunk(EXIF_CHUNK_TYPE, exif)?.clone()
+        } else {
+            let chunk = Chunk::new(ChunkType::from_str(EXIF_CHUNK_TYPE)?, exif);
+
+            png.insert_before_end(chunk.clone());
+            chunk
+        };
+
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok(exif_chunk)
+    }
+}
+
+impl GetExifArgs {
+    pub fn get_exif(&self) -> Result<usize> {
+        // the EXIF data is streamed from the input file, the output is only created once it's found
+        let mut reader = BufReader::new(File::open(&self.file_path)?);
+        let length = stream::find_chunk_data(&mut reader, EXIF_CHUNK_TYPE)?
+            .ok_or(PngError::ChunkNotFoundError)?;
+        let mut writer = BufWriter::new(File::create(&self.output_path)?);
+
+        if self.base64 {
+            stream::copy_exact_base64(&mut reader, &mut writer, length)?;
+        } else {
+            stream::copy_exact(&mut reader, &mut writer, length)?;
+        }
+
+        writer.flush()?;
+
+        Ok(length as usize)
+    }
+}
+
+impl RotateArgs {
+    pub fn rotate(&self) -> Result<Png> {
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from(&buffer[..])?;
+
+        png.rotate_ancillary(self.positions);
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok(png)
+    }
+}
+
+impl MinimizeArgs {
+    pub fn minimize(&self) -> Result<usize> {
+        let buffer = compression::read_file(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let minimal_png = png.minimal()?;
+
+        compression::write_file(
+            self.output_file.as_ref().unwrap_or(&self.file_path),
+            &minimal_png.as_bytes(),
+            self.gzip_level,
+        )?;
+
+        Ok(png.chunks().len() - minimal_png.chunks().len())
+    }
+}
+
+impl SizeImpactArgs {
+    pub fn size_impact(&self) -> Result<Vec<(usize, String, u64)>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        // every chunk takes 12 bytes for its length, type and crc on top of its data
+        let mut savings = png
+            .chunks()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, c.chunk_type().to_string(), 12 + c.length() as u64))
+            .collect::<Vec<(usize, String, u64)>>();
+
+        savings.sort_by_key(|&(_, _, bytes)| std::cmp::Reverse(bytes));
+
+        Ok(savings)
+    }
+}
+
+impl CsvArgs {
+    pub fn csv(&self) -> Result<String> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let mut output = String::from("index,type,length,crc,critical,public,safe_to_copy\n");
+
+        for (i, chunk) in png.chunks().iter().enumerate() {
+            let chunk_type = chunk.chunk_type();
+            let fields = [
+                i.to_string(),
+                chunk_type.to_string(),
+                chunk.length().to_string(),
+                chunk.crc().to_string(),
+                chunk_type.is_critical().to_string(),
+                chunk_type.is_public().to_string(),
+                chunk_type.is_safe_to_copy().to_string(),
+            ];
+
+            output.push_str(
+                &fields
+                    .iter()
+                    .map(|f| csv_field(f))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+impl FindCrcArgs {
+    pub fn find_crc(&self) -> Result<Vec<(usize, String)>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        Ok(png
+            .chunks()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.crc() == self.crc)
+            .map(|(i, c)| (i, c.chunk_type().to_string()))
+            .collect())
+    }
+}
+
+fn parse_crc(s: &str) -> Result<u32> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => Ok(u32::from_str_radix(hex, 16)?),
+        None => Ok(s.parse()?),
+    }
+}
+
+fn parse_gzip_level(s: &str) -> Result<u32> {
+    match s.parse()? {
+        level @ 0..=9 => Ok(level),
+        level => Err(compression::CompressionError::InvalidGzipLevel(level).into()),
+    }
+}
+
+impl TouchArgs {
+    pub fn touch(&self) -> Result<(usize, bool)> {
+        /*
+            every chunk is written again from its data, so the length fields always end up
+            matching it and only the crcs and the order of the chunks need to be fixed
+        */
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from_unchecked(&buffer[..])?;
+        let fixed_crcs = png.fix_crcs();
+        let reordered = png.canonicalize();
+
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok((fixed_crcs, reordered))
+    }
+}
+
+impl EqualArgs {
+    pub fn equal(&self) -> Result<bool> {
+        let first_buffer = fs::read(&self.first_path)?;
+        let second_buffer = fs::read(&self.second_path)?;
+
+        if !self.ignore_order {
+            return Ok(first_buffer == second_buffer);
+        }
+
+        // sorting both lists of chunks turns the comparison of two multisets into a plain one
+        let first_png = Png::try_from(&first_buffer[..])?;
+        let second_png = Png::try_from(&second_buffer[..])?;
+
+        Ok(Self::sorted_chunks(&first_png) == Self::sorted_chunks(&second_png))
+    }
+
+    fn sorted_chunks(png: &Png) -> Vec<&Chunk> {
+        let mut chunks = png.chunks().iter().collect::<Vec<&Chunk>>();
+
+        chunks.sort_by_key(|c| c.as_bytes());
+        chunks
+    }
+}
+
+impl DuplicatesArgs {
+    pub fn duplicates(&self) -> Result<Vec<(ChunkType, usize)>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let mut counts = HashMap::<&ChunkType, usize>::new();
+        let mut duplicates = vec![];
+
+        for chunk in png.chunks() {
+            *counts.entry(chunk.chunk_type()).or_default() += 1;
+        }
+
+        // the duplicated types are reported in the order in which they first appear
+        for chunk in png.chunks() {
+            if let Some(count) = counts.remove(chunk.chunk_type()).filter(|&c| c > 1) {
+                duplicates.push((chunk.chunk_type().clone(), count));
+            }
+        }
+
+        Ok(duplicates)
+    }
+}
+
+impl SealArgs {
+    pub fn seal(&self) -> Result<()> {
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from(&buffer[..])?;
+
+        seal::seal(&mut png)?;
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl CheckSealArgs {
+    pub fn check_seal(&self) -> Result<bool> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        seal::check(&png)
+    }
+}
+
+impl StreamOrderArgs {
+    pub fn stream_order(&self) -> Result<Vec<(usize, String, Option<String>)>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let chunk_types = png
+            .chunks()
+            .iter()
+            .map(|c| c.chunk_type().to_string())
+            .collect::<Vec<String>>();
+
+        // a chunk is too late when a chunk it must precede has already been met by the decoder
+        Ok(chunk_types
+            .iter()
+            .enumerate()
+            .map(|(i, chunk_type)| {
+                let late_after = spec::must_precede(chunk_type)
+                    .iter()
+                    .find(|&&t| chunk_types[..i].iter().any(|c| c == t))
+                    .map(|t| t.to_string());
+
+                (i, chunk_type.clone(), late_after)
+            })
+            .collect())
+    }
+}
+
+impl TextArgs {
+    pub fn text(&self) -> Result<Vec<(String, String)>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        png.chunks()
+            .iter()
+            .filter_map(|c| text::decode_text_chunk(c).transpose())
+            .collect()
+    }
+}
+
+impl CrcDebugArgs {
+    pub fn crc_debug(&self) -> Result<String> {
+        // the file is parsed without checking the crcs, otherwise a bad chunk couldn't be reached
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from_unchecked(&buffer[..])?;
+        let chunk = png
+            .chunk_by_type(&self.chunk_type)
+            .ok_or(PngError::ChunkNotFoundError)?;
+        let input = chunk.crc_input();
+        let hex = input
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        Ok(format!(
+            "Input ({} bytes): {hex}\nCalculated CRC: {:#010x}\nStored CRC: {:#010x}\n",
+            input.len(),
+            chunk.calculated_crc(),
+            chunk.crc()
+        ))
+    }
+}
+
+impl CleanArgs {
+    pub fn clean(&self) -> Result<usize> {
+        let buffer = fs::read(&self.file_path)?;
+        let (png, trailing_data) = Png::split_trailing_data(&buffer)?;
+        let discarded_bytes = trailing_data.len();
+
+        if discarded_bytes > 0 {
+            fs::write(&self.file_path, png.as_bytes())?;
+        }
+
+        Ok(discarded_bytes)
+    }
+}
+
+impl ListArgs {
+    pub fn list(&self) -> Result<Vec<String>> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        Ok(png
+            .chunks()
+            .iter()
+            .map(|c| c.chunk_type().to_string())
+            .collect())
+    }
+}
+
+impl ManifestArgs {
+    pub fn manifest(&self) -> Result<String> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        Ok(serde_json::to_string_pretty(&Manifest::from_png(&png))?)
+    }
+}
+
+impl BuildArgs {
+    pub fn build(&self) -> Result<usize> {
+        let manifest: Manifest = serde_json::from_slice(&fs::read(&self.manifest_path)?)?;
+        let png = manifest.to_png()?;
+
+        fs::write(&self.output_file, png.as_bytes())?;
+
+        Ok(png.chunks().len())
+    }
+}
+
+impl ColorArgs {
+    pub fn color(&self) -> Result<String> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let ihdr = Ihdr::from_png(&png)?;
+
+        Ok(format!(
+            "{}, {}-bit, {} channels",
+            ihdr.color_type,
+            ihdr.bit_depth,
+            ihdr.color_type.channels()
+        ))
+    }
+}
+
+impl DiffBackupArgs {
+    pub fn diff_backup(&self) -> Result<Vec<String>> {
+        let backup_path = diff::backup_path(&self.file_path);
+
+        if !Path::new(&backup_path).exists() {
+            return Err(DiffError::MissingBackup(backup_path).into());
+        }
+
+        let backup_buffer = fs::read(&backup_path)?;
+        let buffer = fs::read(&self.file_path)?;
+        let backup_png = Png::try_from(&backup_buffer[..])?;
+        let png = Png::try_from(&buffer[..])?;
+
+        Ok(diff::diff(&backup_png, &png)
+            .iter()
+            .map(|c| c.to_string())
+            .collect())
+    }
+}
+
+impl ValidateArgs {
+    pub fn validate(&self) -> Result<Vec<(ChunkType, bool)>> {
+        // an invalid signature is still an error, but a bad crc is reported along with its chunk
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from_unchecked(&buffer[..])?;
+
+        Ok(png
+            .chunks()
+            .iter()
+            .map(|c| {
+                let crc = Chunk::calculate_crc(c.chunk_type(), c.data());
+
+                (c.chunk_type().clone(), crc == c.crc())
+            })
+            .collect())
+    }
+}
+
+impl ReorderArgs {
+    pub fn reorder(&self) -> Result<bool> {
+        let config = OrderConfig::load(&self.config)?;
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+
+        match config.reorder(&png) {
+            Some(reordered) => {
+                fs::write(&self.file_path, reordered.as_bytes())?;
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl CapacityReportArgs {
+    pub fn capacity_report(&self) -> Result<Vec<(String, Result<usize, String>)>> {
+        // a file that can't be read or parsed is reported with its error, without losing the others
+        let mut report = Self::png_paths(Path::new(&self.dir_path), self.recursive)?
+            .into_iter()
+            .map(|path| {
+                let hidden_bytes = fs::read(&path)
+                    .map_err(Error::from)
+                    .and_then(|buffer| Self::hidden_bytes(&buffer))
+                    .map_err(|e| e.to_string());
+
+                (path.display().to_string(), hidden_bytes)
+            })
+            .collect::<Vec<(String, Result<usize, String>)>>();
+
+        report.sort();
+
+        Ok(report)
+    }
+
+    fn png_paths(dir_path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+        let mut paths = vec![];
+
+        for entry in fs::read_dir(dir_path)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                if recursive {
+                    paths.extend(Self::png_paths(&path, recursive)?);
+                }
+            } else if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+            {
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+
+    fn hidden_bytes(buffer: &[u8]) -> Result<usize> {
+        // whatever follows IEND and the data of the chunks that decoders don't know about
+        let (png, trailing_data) = Png::split_trailing_data(buffer)?;
+        let custom_data = png
+            .custom_chunks()
+            .iter()
+            .map(|c| c.data().len())
+            .sum::<usize>();
+
+        Ok(trailing_data.len() + custom_data)
+    }
+}
+
+impl ExtractArgs {
+    pub fn extract(&self) -> Result<usize> {
+        validate_chunk_type(&self.chunk_type)?;
+
+        // just like get-exif, the output is only created once the chunk is found
+        let mut reader = BufReader::new(File::open(&self.file_path)?);
+        let length = stream::find_chunk_data(&mut reader, &self.chunk_type)?
+            .ok_or(PngError::ChunkNotFoundError)?;
+        let mut writer = BufWriter::new(File::create(&self.output_path)?);
+
+        stream::copy_exact(&mut reader, &mut writer, length)?;
+        writer.flush()?;
+
+        Ok(length as usize)
+    }
+}
+
+impl GetIccArgs {
+    pub fn get_icc(&self) -> Result<IccProfile> {
+        let buffer = fs::read(&self.file_path)?;
+        let png = Png::try_from(&buffer[..])?;
+        let chunk = png
+            .chunk_by_type(icc::ICCP_CHUNK_TYPE)
+            .ok_or(PngError::ChunkNotFoundError)?;
+        let profile = IccProfile::from_chunk(chunk)?;
+
+        fs::write(&self.output_path, &profile.profile)?;
+
+        Ok(profile)
+    }
+}
+
+impl SetIccArgs {
+    pub fn set_icc(&self) -> Result<Chunk> {
+        let buffer = fs::read(&self.file_path)?;
+        let mut png = Png::try_from(&buffer[..])?;
+        let chunk = IccProfile {
+            name: self.name.clone(),
+            profile: fs::read(&self.profile_path)?,
+        }
+        .to_chunk()?;
+
+        // the iCCP chunk must come before PLTE and IDAT, so a new one goes right after IHDR
+        if png.chunk_by_type(icc::ICCP_CHUNK_TYPE).is_some() {
+            png.replace_chunk(icc::ICCP_CHUNK_TYPE, chunk.data().to_vec())?;
+        } else {
+            png.insert_chunk(1, chunk.clone());
+        }
+
+        fs::write(&self.file_path, png.as_bytes())?;
+
+        Ok(chunk)
+    }
+}
+
+impl CompletionsArgs {
+    pub fn completions(&self) -> Result<String> {
+        let mut script = vec![];
+
+        clap_complete::generate(
+            self.shell,
+            &mut PngMeArgs::command(),
+            env!("CARGO_PKG_NAME"),
+            &mut script,
+        );
+
+        Ok(String::from_utf8(script)?)
+    }
+}
+
+#[cfg(feature = "decode-check")]
+impl DecodeCheckArgs {
+    pub fn decode_check(&self) -> Result<(u32, u32)> {
+        // decoding the pixels also catches chunks that only break the image and not the file
+        let buffer = fs::read(&self.file_path)?;
+        let image = image::load_from_memory_with_format(&buffer, image::ImageFormat::Png)?;
+
+        Ok((image.width(), image.height()))
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    /*
+        from https://www.rfc-editor.org/rfc/rfc4180#section-2
+
+        fields containing commas, quotes or line breaks are enclosed in quotes, doubling the quotes
+        inside them
+    */
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chunk::Chunk,
+        chunk_type::{ChunkType, ChunkTypeError},
+        clipboard::ClipboardError,
+        icc::IccError,
+        ihdr::IhdrError,
+        padding::PaddingError,
+        png::Png,
+        seal::SealError,
+        test_utils::{assert_png_bytes_eq, assert_roundtrip, test_path, MockClipboard},
+        text::TextChunkError,
+    };
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+    use std::{
+        fs::{self, File},
+        str::FromStr,
+    };
+
+    /*
+        since these tests involve file manipulation, each test works with its own files so that
+        they can run concurrently: test_path() prefixes every file name with the name of the test
+    */
+
+    fn file_name() -> String {
+        test_path("test.png")
+    }
+
+    fn output_name() -> String {
+        test_path("output.png")
+    }
+
+    fn invalid_file_name() -> String {
+        test_path("invalid.png")
+    }
+
+    fn data_name() -> String {
+        test_path("data.bin")
+    }
+
+    #[test]
+    fn test_command_line_definition() {
+        PngMeArgs::command().debug_assert();
+    }
+
+    #[test]
+    fn test_fixtures_roundtrip() {
+        assert_roundtrip(&testing_png_simple().as_bytes());
+        assert_roundtrip(&testing_png_full().as_bytes());
+    }
+
+    #[test]
+    fn test_encode_empty_file() {
+        File::create(file_name()).unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            message: Some(String::from("I am the first chunk")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_png_bytes_eq(&png_from_file, &testing_png_simple());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_force_length() {
+        prepare_file(&file_name());
+
+        let original = fs::read(file_name()).unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: Some(1000),
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let contents = fs::read(file_name()).unwrap();
+        let chunk_bytes = &contents[original.len()..];
+
+        assert_eq!(chunk_bytes[..4], 1000u32.to_be_bytes());
+        assert_eq!(&chunk_bytes[4..8], b"TeSt");
+        assert_eq!(&chunk_bytes[8..chunk_bytes.len() - 4], b"I am a test chunk");
+        assert_eq!(
+            chunk_bytes[chunk_bytes.len() - 4..],
+            testing_chunk().unwrap().crc().to_be_bytes()
+        );
+        assert!(Png::try_from(&contents[..]).is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_append_if_missing() {
+        prepare_file(&file_name());
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: true,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert_eq!(encode_args.encode().unwrap(), EncodeOutcome::Modified(None));
+        assert_eq!(encode_args.encode().unwrap(), EncodeOutcome::Unchanged);
+        assert_eq!(EncodeOutcome::Unchanged.exit_code(), 3);
+
+        let mut png = testing_png_full();
+
+        png.append_chunk(testing_chunk().unwrap());
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_expect_hash() {
+        prepare_file(&file_name());
+
+        let hash = Sha256::digest(fs::read(file_name()).unwrap())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        let encode_args = |expect_hash: &str| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: Some(String::from(expect_hash)),
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(matches!(
+            encode_args(&"0".repeat(64))
+                .encode()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(SealError::HashMismatch(..))
+        ));
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+
+        encode_args(&hash).encode().unwrap();
+
+        let mut png = testing_png_full();
+
+        png.append_chunk(testing_chunk().unwrap());
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_strict_text() {
+        prepare_file(&file_name());
+
+        let encode_args = |chunk_type: &str, message: &str| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from(chunk_type),
+            message: Some(String::from(message)),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: true,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(matches!(
+            encode_args("tEXt", "Comment\0I am a 🦀")
+                .encode()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(TextChunkError::NonLatin1Character('🦀'))
+        ));
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+
+        encode_args("iTXt", "Comment\0\0\0\0\0I am a 🦀")
+            .encode()
+            .unwrap();
+        encode_args("tEXt", "Comment\0Café").encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(
+            png.chunk_by_type("tEXt").unwrap().data(),
+            b"Comment\0Caf\xe9"
+        );
+        assert!(png.chunk_by_type("iTXt").is_some());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_reserved_bit_set() {
+        prepare_file(&file_name());
+
+        let encode_args = |allow_reserved_bit_set| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("Rust"),
+            message: Some(String::from("I am an experimental chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(matches!(
+            encode_args(false).encode().err().unwrap().downcast_ref(),
+            Some(ChunkTypeError::ReservedBitSet(_))
+        ));
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+
+        encode_args(true).encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert!(png.chunk_by_type("Rust").is_some());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_creates_new_file_if_not_exists() {
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            message: Some(String::from("I am the first chunk")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_png_bytes_eq(&png_from_file, &testing_png_simple());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_existing_file() {
+        prepare_file(&file_name());
+
+        let new_chunk = testing_chunk().unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let mut expected_png = testing_png_full();
+
+        expected_png.append_chunk(new_chunk);
+        assert_png_bytes_eq(&png_from_file, &expected_png);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_empty_file_with_separate_output() {
+        File::create(file_name()).unwrap();
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            message: Some(String::from("I am the first chunk")),
+            output_file: Some(output_name()),
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+        assert!(fs::read(file_name()).unwrap().is_empty());
+
+        let png_from_empty_file = Png::try_from(&fs::read(file_name()).unwrap()[..]);
+        let png_from_output_file = Png::try_from(&fs::read(output_name()).unwrap()[..]).unwrap();
+
+        assert!(png_from_empty_file.is_err());
+        assert_png_bytes_eq(&png_from_output_file, &testing_png_simple());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_existing_file_with_separate_output() {
+        prepare_file(&file_name());
+
+        let new_chunk = testing_chunk().unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: Some(output_name()),
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_input_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let png_from_output_file = Png::try_from(&fs::read(output_name()).unwrap()[..]).unwrap();
+        let mut expected_png = testing_png_full();
+
+        expected_png.append_chunk(new_chunk);
+        assert_png_bytes_eq(&png_from_input_file, &testing_png_full());
+        assert_png_bytes_eq(&png_from_output_file, &expected_png);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_existing_file_with_existing_output() {
+        prepare_file(&file_name());
+        fs::write(output_name(), testing_png_simple().as_bytes()).unwrap();
+
+        let new_chunk = testing_chunk().unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: Some(output_name()),
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_input_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let png_from_output_file = Png::try_from(&fs::read(output_name()).unwrap()[..]).unwrap();
+        let mut expected_png = testing_png_simple();
+
+        expected_png.append_chunk(new_chunk);
+        assert_png_bytes_eq(&png_from_input_file, &testing_png_full());
+        assert_png_bytes_eq(&png_from_output_file, &expected_png);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_empty_file_with_existing_output() {
+        File::create(file_name()).unwrap();
+        fs::write(output_name(), testing_png_simple().as_bytes()).unwrap();
+
+        let new_chunk = testing_chunk().unwrap();
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: Some(output_name()),
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png_from_output_file = Png::try_from(&fs::read(output_name()).unwrap()[..]).unwrap();
+        let mut expected_png = testing_png_simple();
+
+        expected_png.append_chunk(new_chunk);
+        assert!(fs::read(file_name()).unwrap().is_empty());
+        assert_png_bytes_eq(&png_from_output_file, &expected_png);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_warns_about_chunk_only_output() {
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            message: Some(String::from("I am the first chunk")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+        let outcome = encode_args.encode().unwrap();
+        let warning = outcome.warning().unwrap();
+
+        assert!(warning.contains("IHDR, IDAT, IEND"));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_without_warning() {
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            message: Some(String::from("I am the first chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(encode_args.encode().unwrap() == EncodeOutcome::Modified(None));
+        fs::remove_file(file_name()).unwrap();
+
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+        assert!(EncodeArgs {
+            no_warn: false,
+            ..encode_args
+        }
+        .encode()
+        .unwrap()
+        .warning()
+        .is_none());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_emit_patch() {
+        prepare_file(&file_name());
+
+        let new_chunk = testing_chunk().unwrap();
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: None,
+            no_warn: true,
+            emit_patch: Some(data_name()),
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        encode_args.encode().unwrap();
+
+        let original = fs::read(file_name()).unwrap();
+        let patch = Patch::try_from(&fs::read(data_name()).unwrap()[..]).unwrap();
+        let mut expected_png = testing_png_full();
+
+        expected_png.append_chunk(new_chunk);
+        assert_eq!(original, testing_png_full().as_bytes());
+        assert_eq!(patch.bytes().len(), 12 + 17);
+        assert_eq!(patch.apply(&original).unwrap(), expected_png.as_bytes());
+
+        EncodeArgs {
+            emit_patch: None,
+            ..encode_args
+        }
+        .encode()
+        .unwrap();
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            patch.apply(&original).unwrap()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_emit_patch_missing_file() {
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: Some(data_name()),
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(encode_args.encode().is_err());
+        assert!(!Path::new(&file_name()).exists());
+        assert!(!Path::new(&data_name()).exists());
+    }
+
+    #[test]
+    fn test_encode_at_position() {
+        let ihdr_data = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0];
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+        let new_chunk = testing_chunk().unwrap();
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: Some(output_name()),
+            no_warn: false,
+            emit_patch: None,
+            position: Some(Position::BeforeIdat),
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(encode_args.encode().unwrap() == EncodeOutcome::Modified(None));
+
+        let png_from_file = Png::try_from(&fs::read(output_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png_from_file.chunks()[1].as_bytes(), new_chunk.as_bytes());
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+
+        EncodeArgs {
+            emit_patch: Some(data_name()),
+            ..encode_args
+        }
+        .encode()
+        .unwrap();
+
+        let patch = Patch::try_from(&fs::read(data_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(
+            patch.apply(&png.as_bytes()).unwrap(),
+            png_from_file.as_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_at_index() {
+        prepare_file(&file_name());
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: Some(0),
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        encode_args.encode().unwrap();
+
+        let mut png = testing_png_full();
+
+        png.insert_chunk(0, testing_chunk().unwrap());
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+        assert_eq!(
+            &fs::read(file_name()).unwrap()[8..8 + 29],
+            testing_chunk().unwrap().as_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_at_position_requires_png() {
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: Some(Position::End),
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        // a failed encoding doesn't leave an empty file behind
+        assert!(encode_args.encode().is_err());
+        assert!(!Path::new(&file_name()).exists());
+    }
+
+    #[test]
+    fn test_encode_streaming_matches_full_rewrite() {
+        let ihdr_data = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0];
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data),
+            Chunk::new(
+                ChunkType::from_str("IDAT").unwrap(),
+                vec![0; 8 * 1024 * 1024],
+            ),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+        let new_chunk = testing_chunk().unwrap();
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: new_chunk.chunk_type().to_string(),
+            message: Some(new_chunk.data_as_string().unwrap()),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: true,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(encode_args.encode().unwrap() == EncodeOutcome::Modified(None));
+
+        let streamed_bytes = fs::read(file_name()).unwrap();
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+        EncodeArgs {
+            position: Some(Position::BeforeIend),
+            stream: false,
+            ..encode_args
+        }
+        .encode()
+        .unwrap();
+        assert_eq!(streamed_bytes, fs::read(file_name()).unwrap());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_streaming_warning() {
+        prepare_file(&file_name());
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: true,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(encode_args
+            .encode()
+            .unwrap()
+            .warning()
+            .unwrap()
+            .contains("IHDR, IDAT, IEND"));
+        fs::remove_file(file_name()).unwrap();
+        assert!(encode_args.encode().is_err());
+    }
+
+    #[test]
+    fn test_encode_chunk_type_too_long() {
+        let result = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("abcdefg"),
+            message: Some(String::from("My chunk type is invalid")),
+            output_file: None,
+            no_warn: false,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode();
+
+        assert!(result.is_err());
+        assert!(!Path::new(&file_name()).exists());
+    }
+
+    #[test]
+    fn test_decode_existing_file() {
+        prepare_file(&file_name());
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("FrSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args.decode().unwrap(), "I am the first chunk");
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_does_not_modify_input_file() {
+        prepare_file(&file_name());
+
+        DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("FrSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        }
+        .decode()
+        .unwrap();
+
+        let png_from_input_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_png_bytes_eq(&png_from_input_file, &testing_png_full());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_with_meta() {
+        let png = Png::from_chunks(vec![Chunk::from_strings("tEXt", "hello world").unwrap()]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("tEXt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: true,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args.decode().unwrap(), "tEXt(11): hello world");
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_latin1_text_chunk() {
+        // "Title\0Café" with the é encoded in Latin-1
+        let data = vec![84, 105, 116, 108, 101, 0, 67, 97, 102, 233];
+        let png = Png::from_chunks(vec![Chunk::new(ChunkType::from_str("tEXt").unwrap(), data)]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let mut decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("tEXt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(decode_args.decode().is_err());
+
+        for charset in [Charset::Latin1, Charset::Auto] {
+            decode_args.charset = charset;
+            assert_eq!(decode_args.decode().unwrap(), "Title\0Café");
+        }
+
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_non_existing_file() {
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("FrSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(decode_args.decode().is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_file() {
+        File::create(invalid_file_name()).unwrap();
+
+        let decode_args = DecodeArgs {
+            file_path: invalid_file_name(),
+            chunk_type: Some(String::from("FrSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(decode_args.decode().is_err());
+        fs::remove_file(invalid_file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_valid_file_without_required_chunk() {
+        prepare_file(&file_name());
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("TeSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(decode_args.decode().is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_all_chunks_of_type() {
+        let mut png = testing_png_full();
+
+        png.append_chunk(Chunk::from_strings("miDl", ", split in two").unwrap());
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_args = |all| DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("miDl")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args(false).decode().unwrap(), "I am another chunk");
+        assert_eq!(
+            decode_args(true).decode().unwrap(),
+            "I am another chunk, split in two"
+        );
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_update() {
+        prepare_file(&file_name());
+
+        let encode_args = |chunk_type: &str, message: &str| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from(chunk_type),
+            message: Some(String::from(message)),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: true,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        encode_args("FrSt", "I am the updated first chunk")
+            .encode()
+            .unwrap();
+        encode_args("TeSt", "I am a test chunk").encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let updated_chunk = Chunk::from_strings("FrSt", "I am the updated first chunk").unwrap();
+
+        assert_eq!(png.chunks().len(), 4);
+        assert_eq!(png.chunks_by_type("FrSt").len(), 1);
+        assert_eq!(png.chunks()[0].as_bytes(), updated_chunk.as_bytes());
+        assert_eq!(
+            png.chunk_by_type("TeSt").unwrap().as_bytes(),
+            testing_chunk().unwrap().as_bytes()
+        );
+
+        // updating with the same message leaves every byte as it was
+        let contents = fs::read(file_name()).unwrap();
+
+        assert_eq!(
+            encode_args("FrSt", "I am the updated first chunk")
+                .encode()
+                .unwrap(),
+            EncodeOutcome::Unchanged
+        );
+        assert_eq!(fs::read(file_name()).unwrap(), contents);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_clipboard_round_trip() {
+        prepare_file(&file_name());
+
+        let mut clipboard = MockClipboard {
+            text: Some(String::from("I came from the clipboard")),
+        };
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("ruSt"),
+            message: None,
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: true,
+            allow_duplicate: false,
+        };
+
+        encode_args.encode_from_clipboard(&mut clipboard).unwrap();
+        clipboard.text = None;
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("ruSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: true,
+        };
+
+        decode_args.decode_to_clipboard(&mut clipboard).unwrap();
+
+        assert_eq!(clipboard.text.as_deref(), Some("I came from the clipboard"));
+        assert!(matches!(
+            encode_args
+                .encode_from_clipboard(&mut MockClipboard::default())
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(ClipboardError::NoText)
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_clipboard_allow_duplicate() {
+        prepare_file(&file_name());
+
+        let mut clipboard = MockClipboard {
+            text: Some(String::from("I came from the clipboard")),
+        };
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("ruSt"),
+            message: None,
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: true,
+            allow_duplicate: true,
+        };
+
+        encode_args.encode_from_clipboard(&mut clipboard).unwrap();
+        encode_args.encode_from_clipboard(&mut clipboard).unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunks_by_type("ruSt").len(), 2);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_duplicate_rejected_by_default() {
+        prepare_file(&file_name());
+
+        let encode_args = |allow_duplicate| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate,
+        };
+
+        encode_args(false).encode().unwrap();
+
+        let contents = fs::read(file_name()).unwrap();
+
+        assert!(matches!(
+            encode_args(false).encode().err().unwrap().downcast_ref(),
+            Some(PngError::DuplicateChunk(t)) if t == "TeSt"
+        ));
+        assert_eq!(fs::read(file_name()).unwrap(), contents);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_duplicate_allowed() {
+        prepare_file(&file_name());
+
+        let encode_args = |allow_duplicate| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate,
+        };
+
+        encode_args(false).encode().unwrap();
+        encode_args(true).encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunks_by_type("TeSt").len(), 2);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_duplicate_checked_in_output() {
+        let mut png = testing_png_full();
+
+        png.append_chunk(testing_chunk().unwrap());
+        prepare_file(&file_name());
+        fs::write(output_name(), png.as_bytes()).unwrap();
+
+        let encode_args = |file_path: String, output_file: String| EncodeArgs {
+            file_path,
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: Some(output_file),
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        // the input is clean, but the output the chunk would be appended to already holds one
+        assert!(matches!(
+            encode_args(file_name(), output_name())
+                .encode()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(PngError::DuplicateChunk(t)) if t == "TeSt"
+        ));
+        assert_eq!(fs::read(output_name()).unwrap(), png.as_bytes());
+
+        // the other way around, the chunk of the input doesn't end up in the output
+        encode_args(output_name(), file_name()).encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunks_by_type("TeSt").len(), 1);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_pad_and_unpad_round_trip() {
+        prepare_file(&file_name());
+
+        let encode_args = |pad_to| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: Some(pad_to),
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(matches!(
+            encode_args(20).encode().err().unwrap().downcast_ref(),
+            Some(PaddingError::MessageTooLong(21, 20))
+        ));
+
+        encode_args(256).encode().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunk_by_type("TeSt").unwrap().length(), 256);
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("TeSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: true,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args.decode().unwrap(), "I am a test chunk");
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_message_file() {
+        let payload = (0..=255).rev().collect::<Vec<u8>>();
+
+        prepare_file(&file_name());
+        fs::write(data_name(), &payload).unwrap();
+
+        let encode_args = EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("ruSt"),
+            message: None,
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: Some(data_name()),
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        encode_args.encode().unwrap();
+
+        let extract_args = ExtractArgs {
+            file_path: file_name(),
+            chunk_type: String::from("ruSt"),
+            output_path: output_name(),
+        };
+
+        assert_eq!(extract_args.extract().unwrap(), payload.len());
+        assert_eq!(fs::read(output_name()).unwrap(), payload);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_message_or_message_file() {
+        let parse = |args: &[&str]| {
+            PngMeArgs::try_parse_from([&["pngme", "encode", &file_name(), "ruSt"], args].concat())
+        };
+
+        assert!(parse(&["message"]).is_ok());
+        assert!(parse(&["--message-file", &data_name()]).is_ok());
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["message", "--message-file", &data_name()]).is_err());
+        assert!(parse(&["--message-file", &data_name(), "--strict-text"]).is_err());
+    }
+
+    #[test]
+    fn test_encode_without_message() {
+        prepare_file(&file_name());
+        fs::write(data_name(), b"I am a test chunk").unwrap();
+
+        let encode_args = |message_file: Option<String>, strict_text| EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("tEXt"),
+            message: None,
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        };
+
+        assert!(matches!(
+            encode_args(None, false)
+                .encode()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(EncodeError::MissingMessage)
+        ));
+        assert!(matches!(
+            encode_args(Some(data_name()), true)
+                .encode()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(EncodeError::StrictTextFromFile)
+        ));
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let message = "I am a 🦀 in UTF-16";
+        let mut png = testing_png_full();
+
+        png.append_chunk(Chunk::new(
+            ChunkType::from_str("ruSt").unwrap(),
+            message.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        ));
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_args = |utf16le, utf16be| DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("ruSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le,
+            utf16be,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args(true, false).decode().unwrap(), message);
+        assert_ne!(decode_args(false, true).decode().unwrap(), message);
+        assert!(decode_args(false, false).decode().is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_show_index() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("tEXt", "Title\0First").unwrap(),
+            Chunk::from_strings("tEXt", "Title\0Second").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("tEXt")),
+            auto: false,
+            charset: Charset::Latin1,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: true,
+            show_index: true,
+            to_clipboard: false,
+        };
+
+        assert_eq!(
+            decode_args.decode().unwrap(),
+            "Chunk 0: Title\0First\nChunk 1: Title\0Second"
+        );
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_from_reader() {
+        let decode_args = DecodeArgs {
+            file_path: String::from("-"),
+            chunk_type: Some(String::from("miDl")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+        let bytes = testing_png_full().as_bytes();
+
+        assert_eq!(
+            decode_args.decode_from(&bytes[..]).unwrap(),
+            "I am another chunk"
+        );
+    }
+
+    #[test]
+    fn test_decode_too_long_chunk_type() {
+        prepare_file(&file_name());
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("abcdefg")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(matches!(
+            decode_args.decode().err().unwrap().downcast_ref(),
+            Some(ChunkError::InvalidChunkType(
+                ChunkTypeError::InvalidStringLength(7)
+            ))
+        ));
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("abcdefg"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+
+        assert!(matches!(
+            remove_args.remove().err().unwrap().downcast_ref(),
+            Some(ChunkError::InvalidChunkType(_))
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_auto_single_custom_chunk() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("tEXt", "Comment\0Not a secret").unwrap(),
+            Chunk::from_strings("ruSt", "I am the only custom chunk").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: None,
+            auto: true,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert_eq!(decode_args.decode().unwrap(), "I am the only custom chunk");
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_decode_auto_ambiguous_custom_chunks() {
+        prepare_file(&file_name());
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: None,
+            auto: true,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+        let error = decode_args.decode().unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<PngError>(),
+            Some(PngError::AmbiguousChunkError(3))
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_existing_file() {
+        prepare_file(&file_name());
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+        let removed_chunk = remove_args.remove().unwrap();
+        let testing_chunk = Chunk::from_strings("FrSt", "I am the first chunk").unwrap();
+
+        assert_eq!(removed_chunk.as_bytes(), testing_chunk.as_bytes());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_does_modify_input_file() {
+        prepare_file(&file_name());
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+        let mut png = testing_png_full();
+
+        remove_args.remove().unwrap();
+        png.remove_chunk("FrSt").unwrap();
+
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_png_bytes_eq(&png_from_file, &png);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_non_existing_file() {
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+
+        assert!(remove_args.remove().is_err());
+    }
+
+    #[test]
+    fn test_missing_and_invalid_file_errors() {
+        const MISSING_FILE_NAME: &str = "missing.png";
+
+        File::create(invalid_file_name()).unwrap();
+
+        let errors = |file_path: &str| {
+            let decode = DecodeArgs {
+                file_path: String::from(file_path),
+                chunk_type: Some(String::from("FrSt")),
+                auto: false,
+                charset: Charset::Utf8,
+                unpad: false,
+                utf16le: false,
+                utf16be: false,
+                with_meta: false,
+                all: false,
+                show_index: false,
+                to_clipboard: false,
+            }
+            .decode();
+            let remove = RemoveArgs {
+                file_path: String::from(file_path),
+                chunk_type: String::from("FrSt"),
+                stream: false,
+                all: false,
+                check: false,
+                keep_empty: true,
+            }
+            .remove();
+            let print = PrintArgs {
+                file_path: String::from(file_path),
+                human: false,
+                annotate: false,
+                detect_compression: false,
+                verbose: false,
+                tree: false,
+                offset: 0,
+                length: None,
+                format: OutputFormat::Text,
+                headers_only: false,
+            }
+            .print();
+
+            [
+                decode.err().unwrap().to_string(),
+                remove.err().unwrap().to_string(),
+                print.err().unwrap().to_string(),
+            ]
+        };
+
+        for (missing, invalid) in errors(MISSING_FILE_NAME)
+            .iter()
+            .zip(errors(&invalid_file_name()))
+        {
+            assert_eq!(missing, "file not found: missing.png");
+            assert!(invalid.starts_with("not a valid PNG: "));
+        }
+        fs::remove_file(invalid_file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_check() {
+        let png = Png::from_chunks(
+            ["IHDR", "IDAT", "tEXt", "IEND"]
+                .iter()
+                .map(|t| Chunk::from_strings(t, "").unwrap())
+                .collect(),
+        );
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let remove_args = |chunk_type: &str, check| RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from(chunk_type),
+            stream: false,
+            all: false,
+            check,
+            keep_empty: true,
+        };
+
+        assert!(remove_args("IDAT", true).check().unwrap().is_some());
+        assert!(remove_args("IHDR", true).check().unwrap().is_some());
+        assert!(remove_args("tEXt", true).check().unwrap().is_none());
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+
+        // the check is only advisory, a plain remove still does what it's told
+        remove_args("IDAT", false).remove().unwrap();
+
+        assert_eq!(
+            Png::try_from(&fs::read(file_name()).unwrap()[..])
+                .unwrap()
+                .chunks()
+                .len(),
+            3
+        );
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_invalid_file() {
+        File::create(invalid_file_name()).unwrap();
+
+        let remove_args = RemoveArgs {
+            file_path: invalid_file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+
+        assert!(remove_args.remove().is_err());
+        fs::remove_file(invalid_file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_valid_file_without_required_chunk() {
+        prepare_file(&file_name());
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+        let result = remove_args.remove();
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert!(result.is_err());
+        assert_png_bytes_eq(&png_from_file, &testing_png_full());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_deletes_file_after_removing_last_chunk() {
+        File::create(file_name()).unwrap();
+        fs::write(file_name(), testing_png_simple().as_bytes()).unwrap();
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        };
+
+        remove_args.remove().unwrap();
+        assert!(File::open(file_name()).is_err());
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut png = testing_png_full();
+
+        png.append_chunk(testing_chunk().unwrap());
+        png.append_chunk(testing_chunk().unwrap());
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let remove_args = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            stream: false,
+            all: true,
+            check: false,
+            keep_empty: false,
+        };
+
+        assert_eq!(remove_args.remove_all().unwrap(), 2);
+        assert_png_bytes_eq(
+            &Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap(),
+            &testing_png_full(),
+        );
+        assert!(matches!(
+            remove_args.remove_all().err().unwrap().downcast_ref(),
+            Some(PngError::ChunkNotFoundError)
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_keeps_empty_file() {
+        for stream in [false, true] {
+            fs::write(file_name(), testing_png_simple().as_bytes()).unwrap();
+
+            RemoveArgs {
+                file_path: file_name(),
+                chunk_type: String::from("FrSt"),
+                stream,
+                all: false,
+                check: false,
+                keep_empty: true,
+            }
+            .remove()
+            .unwrap();
+
+            assert_eq!(fs::read(file_name()).unwrap(), Png::STANDARD_HEADER);
+            fs::remove_file(file_name()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_debug_path() {
+        let args = PngMeArgs::parse_from(["pngme", "print", &file_name(), "--debug"]);
+
+        assert_eq!(args.debug_path().as_deref(), Some(file_name().as_str()));
+
+        let args = PngMeArgs::parse_from(["pngme", "print", &file_name()]);
+
+        assert!(args.debug_path().is_none());
+
+        let args = PngMeArgs::parse_from(["pngme", "--debug", "completions", "bash"]);
+
+        assert!(args.debug_path().is_none());
+    }
+
+    #[test]
+    fn test_keep_empty_precedence() {
+        let variable = |value: &str| Some(String::from(value));
+
+        assert!(!PngMeArgs::resolve_keep_empty(false, false, None));
+        assert!(PngMeArgs::resolve_keep_empty(true, false, None));
+        assert!(PngMeArgs::resolve_keep_empty(false, false, variable("1")));
+        assert!(!PngMeArgs::resolve_keep_empty(false, false, variable("0")));
+        assert!(!PngMeArgs::resolve_keep_empty(false, true, variable("1")));
+        assert!(PngMeArgs::resolve_keep_empty(true, false, variable("0")));
+    }
+
+    #[test]
+    fn test_global_keep_empty_flag() {
+        let args = PngMeArgs::parse_from(["pngme", "remove", &file_name(), "FrSt", "--keep-empty"]);
+
+        assert!(matches!(
+            args.configured_command(),
+            CommandType::Remove(RemoveArgs {
+                keep_empty: true,
+                ..
+            })
+        ));
+
+        let args =
+            PngMeArgs::parse_from(["pngme", "--delete-empty", "remove", &file_name(), "FrSt"]);
+
+        assert!(matches!(
+            args.configured_command(),
+            CommandType::Remove(RemoveArgs {
+                keep_empty: false,
+                ..
+            })
+        ));
+        assert!(PngMeArgs::try_parse_from([
+            "pngme",
+            "remove",
+            &file_name(),
+            "FrSt",
+            "--keep-empty",
+            "--delete-empty"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_remove_streaming_matches_buffered_remove() {
+        let mut png = testing_png_full();
+
+        png.append_chunk(Chunk::from_strings("miDl", "I am a duplicate chunk").unwrap());
+        png.append_chunk(testing_chunk().unwrap());
+        fs::write(file_name(), png.as_bytes()).unwrap();
+        fs::write(output_name(), png.as_bytes()).unwrap();
+
+        let buffered_chunk = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("miDl"),
+            stream: false,
+            all: false,
+            check: false,
+            keep_empty: false,
+        }
+        .remove()
+        .unwrap();
+        let streamed_chunk = RemoveArgs {
+            file_path: output_name(),
+            chunk_type: String::from("miDl"),
+            stream: true,
+            all: false,
+            check: false,
+            keep_empty: false,
+        }
+        .remove()
+        .unwrap();
+
+        assert_eq!(streamed_chunk.as_bytes(), buffered_chunk.as_bytes());
+        assert_eq!(
+            fs::read(output_name()).unwrap(),
+            fs::read(file_name()).unwrap()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_streaming_without_required_chunk() {
+        prepare_file(&file_name());
+
+        let result = RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            stream: true,
+            all: false,
+            check: false,
+            keep_empty: false,
+        }
+        .remove();
+
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+        assert!(File::open(format!("{}.tmp", file_name())).is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_remove_streaming_deletes_file_after_removing_last_chunk() {
+        fs::write(file_name(), testing_png_simple().as_bytes()).unwrap();
+
+        RemoveArgs {
+            file_path: file_name(),
+            chunk_type: String::from("FrSt"),
+            stream: true,
+            all: false,
+            check: false,
+            keep_empty: false,
+        }
+        .remove()
+        .unwrap();
+
+        assert!(File::open(file_name()).is_err());
+    }
+
+    #[test]
+    fn test_print_existing_file() {
+        prepare_file(&file_name());
+
+        let print_args = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        };
+
+        assert_eq!(print_args.print().unwrap(), testing_png_full().to_string());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_human_readable_sizes() {
+        let png = Png::from_chunks(vec![Chunk::new(
+            ChunkType::from_str("RuSt").unwrap(),
+            vec![0; 1_048_576],
+        )]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let human_output = PrintArgs {
+            file_path: file_name(),
+            human: true,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+        let raw_output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+        let tree_output = PrintArgs {
+            file_path: file_name(),
+            human: true,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: true,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert!(human_output.contains("Data: 1.0 MiB"));
+        assert!(raw_output.contains("Data: 1048576 bytes"));
+        assert!(tree_output.contains("Chunk 0: RuSt (1.0 MiB)"));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_headers_only() {
+        prepare_file(&file_name());
+
+        let print_args = |headers_only| PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only,
+        };
+        let lines = |output: String, prefix: &str| {
+            output
+                .lines()
+                .filter(|l| l.starts_with(prefix))
+                .map(String::from)
+                .collect::<Vec<String>>()
+        };
+        let headers_output = print_args(true).print().unwrap();
+        let full_output = print_args(false).print().unwrap();
+
+        assert_eq!(
+            lines(headers_output.clone(), "  Type: "),
+            ["  Type: FrSt", "  Type: miDl", "  Type: LASt"]
+        );
+        assert_eq!(
+            lines(headers_output.clone(), "  Type: "),
+            lines(full_output.clone(), "  Type: ")
+        );
+        assert_eq!(
+            lines(headers_output.clone(), "  Length: "),
+            lines(full_output, "  Length: ")
+        );
+        assert!(headers_output.starts_with("PNG with 3 chunks\n"));
+        assert!(!headers_output.contains("Crc"));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_interlaced_image() {
+        let ihdr_data = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 1];
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert!(output.starts_with(&png.to_string()));
+        assert!(output.ends_with("Image: 1x1, RGB, 8-bit, interlaced: yes\n"));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_with_sidecar_descriptions() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("tEXt", "Comment\0I am a comment").unwrap(),
+            Chunk::from_strings("miDl", "I am another chunk").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+        fs::write(
+            sidecar::path_for(&file_name()),
+            r#"{"tEXt": "A comment about the image", "zTXt": "Compressed text"}"#,
+        )
+        .unwrap();
+
+        let print_args = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: true,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        };
+
+        assert_eq!(
+            print_args.print().unwrap(),
+            format!("{png}Descriptions:\n  tEXt: A comment about the image\n")
+        );
+        fs::remove_file(sidecar::path_for(&file_name())).unwrap();
+        assert_eq!(print_args.print().unwrap(), png.to_string());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_verbose() {
+        let chunk_type = ChunkType::from_str("RuSt").unwrap();
+
+        assert!(chunk_type.is_critical());
+        assert!(!chunk_type.is_public());
+        assert!(chunk_type.is_reserved_bit_valid());
+        assert!(chunk_type.is_safe_to_copy());
+
+        fs::write(
+            file_name(),
+            Png::from_chunks(vec![Chunk::new(chunk_type, vec![])]).as_bytes(),
+        )
+        .unwrap();
+
+        let output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: true,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert!(output.ends_with(
+            "Properties:\n  Chunk 0 (RuSt): critical: true, public: false, reserved bit valid: true, safe to copy: true\n"
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_detect_compression() {
+        let mut ztxt_data = b"Comment\0\0".to_vec();
+
+        ztxt_data.extend(compression::deflate(b"I am a compressed comment").unwrap());
+
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("zTXt").unwrap(), ztxt_data),
+            Chunk::from_strings("zTXt", "Comment\0\x05I am compressed somehow").unwrap(),
+            Chunk::from_strings("tEXt", "Comment\0I am a plain comment").unwrap(),
+            Chunk::new(
+                ChunkType::from_str("ruSt").unwrap(),
+                compression::deflate(b"I am a compressed chunk").unwrap(),
+            ),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: true,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert!(output.ends_with(
+            "Compression:\n  Chunk 0 (zTXt): zlib after 9 bytes, method 0 (zlib)\n  Chunk 1 (zTXt): raw, method 5 (nonstandard)\n  Chunk 2 (tEXt): raw\n  Chunk 3 (ruSt): zlib\n"
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_tree_groups_animation_frames() {
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 13]),
+            Chunk::new(ChunkType::from_str("acTL").unwrap(), vec![0; 8]),
+            Chunk::new(ChunkType::from_str("fcTL").unwrap(), vec![0; 26]),
+            Chunk::new(ChunkType::from_str("IDAT").unwrap(), vec![0; 10]),
+            Chunk::new(ChunkType::from_str("fcTL").unwrap(), vec![0; 26]),
+            Chunk::new(ChunkType::from_str("fdAT").unwrap(), vec![0; 14]),
+            Chunk::new(ChunkType::from_str("fdAT").unwrap(), vec![0; 14]),
+            Chunk::from_strings("tEXt", "Comment\0I am a comment").unwrap(),
+            Chunk::new(ChunkType::from_str("IEND").unwrap(), vec![]),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: true,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert!(output.starts_with(
+            "PNG with 9 chunks
+  Chunk 0: IHDR (13 bytes)
+  Image data
+    Chunk 3: IDAT (10 bytes)
+  Animation
+    Chunk 1: acTL (8 bytes)
+    Frame 0
+      Chunk 2: fcTL (26 bytes)
+    Frame 1
+      Chunk 4: fcTL (26 bytes)
+      Chunk 5: fdAT (14 bytes)
+      Chunk 6: fdAT (14 bytes)
+  Metadata
+    Chunk 7: tEXt (22 bytes)
+  Chunk 8: IEND (0 bytes)
+"
+        ));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_header_only_file() {
+        fs::write(file_name(), Png::STANDARD_HEADER).unwrap();
+
+        let print_output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+
+        assert_eq!(print_output, "PNG with 0 chunks (header-only file)\n");
+
+        let decode_args = DecodeArgs {
+            file_path: file_name(),
+            chunk_type: Some(String::from("TeSt")),
+            auto: false,
+            charset: Charset::Utf8,
+            unpad: false,
+            utf16le: false,
+            utf16be: false,
+            with_meta: false,
+            all: false,
+            show_index: false,
+            to_clipboard: false,
+        };
+
+        assert!(matches!(
+            decode_args.decode().err().unwrap().downcast_ref(),
+            Some(PngError::ChunkNotFoundError)
+        ));
+
+        EncodeArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            message: Some(String::from("I am a test chunk")),
+            output_file: None,
+            no_warn: true,
+            emit_patch: None,
+            position: None,
+            index: None,
+            stream: false,
+            force_length: None,
+            strict_text: false,
+            allow_reserved_bit_set: false,
+            expect_hash: None,
+            append_if_missing: false,
+            pad_to: None,
+            message_file: None,
+            update: false,
+            from_clipboard: false,
+            allow_duplicate: false,
+        }
+        .encode()
+        .unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunks().len(), 1);
+        assert_eq!(decode_args.decode().unwrap(), "I am a test chunk");
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_embedded_png() {
+        let png_bytes = testing_png_full().as_bytes();
+        let mut container = vec![0xaa; 4096];
+
+        container.extend(&png_bytes);
+        container.extend([0xbb; 100]);
+        fs::write(file_name(), &container).unwrap();
+
+        let print_args = |offset, length| PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset,
+            length,
+            format: OutputFormat::Text,
+            headers_only: false,
+        };
+
+        assert_eq!(
+            print_args(4096, Some(png_bytes.len())).print().unwrap(),
+            testing_png_full().to_string()
+        );
+        assert!(print_args(4096, None).print().is_err());
+        assert!(print_args(0, Some(png_bytes.len())).print().is_err());
+        assert!(matches!(
+            print_args(4096, Some(container.len()))
+                .print()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(PngError::RangeOutOfBounds(4096, _, _))
+        ));
+        assert!(print_args(usize::MAX, Some(1)).print().is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_json() {
+        prepare_file(&file_name());
+
+        let output = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Json,
+            headers_only: false,
+        }
+        .print()
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let chunk_types = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["type"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(chunk_types, ["FrSt", "miDl", "LASt"]);
+        assert_eq!(json[0]["data_len"], 20);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_print_non_existing_file() {
+        let print_args = PrintArgs {
+            file_path: file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        };
+
+        assert!(print_args.print().is_err());
+    }
+
+    #[test]
+    fn test_print_invalid_file() {
+        File::create(invalid_file_name()).unwrap();
+
+        let print_args = PrintArgs {
+            file_path: invalid_file_name(),
+            human: false,
+            annotate: false,
+            detect_compression: false,
+            verbose: false,
+            tree: false,
+            offset: 0,
+            length: None,
+            format: OutputFormat::Text,
+            headers_only: false,
+        };
+
+        assert!(print_args.print().is_err());
+        fs::remove_file(invalid_file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_replace_from_binary_file() {
+        prepare_file(&file_name());
+
+        let binary_data = vec![0, 159, 146, 150, 255, 0, 13, 10];
+
+        fs::write(data_name(), &binary_data).unwrap();
+
+        let replaced_chunk = ReplaceFromArgs {
+            file_path: file_name(),
+            chunk_type: String::from("miDl"),
+            data_path: data_name(),
+        }
+        .replace_from()
+        .unwrap();
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let extracted_chunk = png_from_file.chunk_by_type("miDl").unwrap();
+
+        assert_eq!(replaced_chunk.data(), &binary_data[..]);
+        assert_eq!(extracted_chunk.data(), &binary_data[..]);
+        assert_eq!(extracted_chunk.as_bytes(), replaced_chunk.as_bytes());
+        assert_eq!(png_from_file.chunks().len(), 3);
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_replace_from_valid_file_without_required_chunk() {
+        prepare_file(&file_name());
+        fs::write(data_name(), [1, 2, 3]).unwrap();
+
+        let result = ReplaceFromArgs {
+            file_path: file_name(),
+            chunk_type: String::from("TeSt"),
+            data_path: data_name(),
+        }
+        .replace_from();
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert!(result.is_err());
+        assert_png_bytes_eq(&png_from_file, &testing_png_full());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_exif_against_spec_versions() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("eXIf", "").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let issues_1_2 = VerifyArgs {
+            file_path: file_name(),
+            spec: SpecVersion::V1_2,
+            werror: false,
+            json: false,
+        }
+        .verify()
+        .unwrap();
+        let issues_3 = VerifyArgs {
+            file_path: file_name(),
+            spec: SpecVersion::V3,
+            werror: false,
+            json: false,
+        }
+        .verify()
+        .unwrap();
+
+        assert_eq!(issues_1_2.len(), 1);
+        assert_eq!(
+            issues_1_2[0].to_string(),
+            "Chunk 1: eXIf is an unknown ancillary chunk for PNG 1.2"
+        );
+        assert!(issues_3.is_empty());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_werror() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("ruSt", "I am an unknown chunk").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let mut verify_args = VerifyArgs {
+            file_path: file_name(),
+            spec: SpecVersion::default(),
+            werror: false,
+            json: false,
+        };
+        let issues = verify_args.verify().unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(verify_args.exit_code(&issues), 0);
+        verify_args.werror = true;
+        assert_eq!(verify_args.exit_code(&issues), 1);
+        assert_eq!(verify_args.exit_code(&[]), 0);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_errors_fail_without_werror() {
+        let png = Png::from_chunks(vec![Chunk::from_strings("IHDR", "").unwrap()]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let verify_args = VerifyArgs {
+            file_path: file_name(),
+            spec: SpecVersion::default(),
+            werror: false,
+            json: false,
+        };
+        let issues = verify_args.verify().unwrap();
+
+        assert_eq!(verify_args.exit_code(&issues), 1);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_json_reports_bad_crc() {
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 13]),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+        let mut bytes = png.as_bytes();
+        // the crc of IDAT ends right before the 12 bytes of IEND
+        let idat_crc = bytes.len() - 13;
+
+        bytes[idat_crc] ^= 1;
+        fs::write(file_name(), bytes).unwrap();
+
+        let verify_args = VerifyArgs {
+            file_path: file_name(),
+            spec: SpecVersion::default(),
+            werror: false,
+            json: true,
+        };
+        let issues = verify_args.verify().unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&verify_args.json_report(&issues).unwrap()).unwrap();
+
+        assert_eq!(report["ok"], false);
+        assert_eq!(report["issues"].as_array().unwrap().len(), 1);
+        assert_eq!(report["issues"][0]["chunk_index"], 1);
+        assert_eq!(report["issues"][0]["chunk_type"], "IDAT");
+        assert_eq!(report["issues"][0]["severity"], "error");
+        assert!(report["issues"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("CRC"));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_invalid_file() {
+        File::create(invalid_file_name()).unwrap();
+
+        let verify_args = VerifyArgs {
+            file_path: invalid_file_name(),
+            spec: SpecVersion::default(),
+            werror: false,
+            json: false,
+        };
+
+        assert!(verify_args.verify().is_err());
+        fs::remove_file(invalid_file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_set_and_get_exif_round_trip() {
+        prepare_file(&file_name());
+
+        let exif = vec![b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 0, 255, 254];
+
+        fs::write(data_name(), [b'I', b'I', 42, 0]).unwrap();
+        SetExifArgs {
+            file_path: file_name(),
+            exif_path: data_name(),
+        }
+        .set_exif()
+        .unwrap();
+        fs::write(data_name(), &exif).unwrap();
+        SetExifArgs {
+            file_path: file_name(),
+            exif_path: data_name(),
+        }
+        .set_exif()
+        .unwrap();
+
+        let extracted_bytes = GetExifArgs {
+            file_path: file_name(),
+            output_path: output_name(),
+            base64: false,
+        }
+        .get_exif()
+        .unwrap();
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let exif_chunks = png_from_file
+            .chunks()
+            .iter()
+            .filter(|c| c.chunk_type().to_string() == "eXIf")
+            .count();
+
+        assert_eq!(extracted_bytes, exif.len());
+        assert_eq!(fs::read(output_name()).unwrap(), exif);
+        assert_eq!(exif_chunks, 1);
+
+        GetExifArgs {
+            file_path: file_name(),
+            output_path: output_name(),
+            base64: true,
+        }
+        .get_exif()
+        .unwrap();
+
+        assert_eq!(
+            fs::read(output_name()).unwrap(),
+            base64::engine::general_purpose::STANDARD
+                .encode(&exif)
+                .into_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+    }
+
+    #[test]
+    fn test_get_exif_valid_file_without_exif() {
+        prepare_file(&file_name());
+
+        let get_exif_args = GetExifArgs {
+            file_path: file_name(),
+            output_path: output_name(),
+            base64: false,
+        };
+
+        assert!(get_exif_args.get_exif().is_err());
+        assert!(File::open(output_name()).is_err());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_middle_chunks() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("frSt", "I am the first chunk").unwrap(),
+            Chunk::from_strings("miDl", "I am another chunk").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("laSt", "I am the last chunk").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+        RotateArgs {
+            file_path: file_name(),
+            positions: -1,
+        }
+        .rotate()
+        .unwrap();
+
+        let png_from_file = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+        let types = png_from_file
+            .chunks()
+            .iter()
+            .map(|c| c.chunk_type().to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(types, vec!["IHDR", "miDl", "laSt", "IDAT", "frSt", "IEND"]);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_minimize_with_separate_output() {
+        let ihdr_data = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0];
+        let png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data.clone()),
+            Chunk::from_strings("PLTE", "\0\0\0").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("ruSt", "I am a custom chunk").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let removed_chunks = MinimizeArgs {
+            file_path: file_name(),
+            output_file: Some(output_name()),
+            gzip_level: compression::DEFAULT_GZIP_LEVEL,
+        }
+        .minimize()
+        .unwrap();
+        let expected_png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        assert_eq!(removed_chunks, 2);
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+        assert_eq!(fs::read(output_name()).unwrap(), expected_png.as_bytes());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+    }
+
+    #[test]
+    fn test_minimize_to_gzip_levels() {
+        let gzip_name = &test_path("output.png.gz");
+
+        fs::write(file_name(), crate::test_utils::PNG_FILE).unwrap();
+
+        let gzip_size = |gzip_level| {
+            MinimizeArgs {
+                file_path: file_name(),
+                output_file: Some(String::from(gzip_name)),
+                gzip_level,
+            }
+            .minimize()
+            .unwrap();
+
+            let compressed = fs::read(gzip_name).unwrap();
+
+            assert_eq!(
+                compression::read_file(gzip_name).unwrap(),
+                compression::gunzip(&compressed).unwrap()
+            );
+            assert!(Png::try_from(&compression::gunzip(&compressed).unwrap()[..]).is_ok());
+            compressed.len()
+        };
+
+        assert!(gzip_size(9) <= gzip_size(0));
+        assert!(parse_gzip_level("10").is_err());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(gzip_name).unwrap();
+    }
+
+    #[test]
+    fn test_minimize_without_ihdr() {
+        prepare_file(&file_name());
+
+        let minimize_args = MinimizeArgs {
+            file_path: file_name(),
+            output_file: None,
+            gzip_level: compression::DEFAULT_GZIP_LEVEL,
+        };
+
+        assert!(minimize_args.minimize().is_err());
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            testing_png_full().as_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_size_impact() {
+        prepare_file(&file_name());
+
+        let savings = SizeImpactArgs {
+            file_path: file_name(),
+        }
+        .size_impact()
+        .unwrap();
+        let png = testing_png_full();
+
+        assert_eq!(savings.len(), png.chunks().len());
+        assert!(savings.windows(2).all(|w| w[0].2 >= w[1].2));
+
+        for (index, chunk_type, bytes) in savings {
+            let chunk = &png.chunks()[index];
+
+            assert_eq!(chunk_type, chunk.chunk_type().to_string());
+            assert_eq!(bytes, 12 + chunk.length() as u64);
+        }
+
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_csv() {
+        prepare_file(&file_name());
+
+        let output = CsvArgs {
+            file_path: file_name(),
+        }
+        .csv()
+        .unwrap();
+        let png = testing_png_full();
+        let mut lines = output.lines();
+
+        assert_eq!(
+            lines.next().unwrap(),
+            "index,type,length,crc,critical,public,safe_to_copy"
+        );
+
+        let rows = lines
+            .map(|l| l.split(',').collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+
+        assert_eq!(rows.len(), png.chunks().len());
+
+        for (row, chunk) in rows.iter().zip(png.chunks()) {
+            assert_eq!(row.len(), 7);
+            assert_eq!(row[1], chunk.chunk_type().to_string());
+            assert_eq!(row[2], chunk.length().to_string());
+            assert_eq!(row[3], chunk.crc().to_string());
         }
+
+        assert_eq!(
+            rows[0],
+            vec!["0", "FrSt", "20", &rows[0][3], "true", "false", "true"]
+        );
+        assert_eq!(rows[2][4..], ["true", "true", "true"]);
+        fs::remove_file(file_name()).unwrap();
     }
 
-    fn validate_input(input_buffer: &Vec<u8>, chunk: Chunk) -> Result<Vec<u8>> {
-        match Self::validate_png(input_buffer) {
-            FileState::Png => Ok(chunk.as_bytes()), // valid input
-            FileState::Empty => Ok(Png::from_chunks(vec![chunk]).as_bytes()), // empty input
-            FileState::Other(e) => Err(e), // invalid input
-        }
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("FrSt"), "FrSt");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
     }
-}
 
-impl DecodeArgs {
-    pub fn decode(&self) -> Result<String> {
-        let buffer = fs::read(&self.file_path)?;
-        let png = Png::try_from(&buffer[..])?;
+    #[test]
+    fn test_find_crc() {
+        let mut png = testing_png_full();
 
-        match png.chunk_by_type(&self.chunk_type) {
-            Some(data) => data.data_as_string(),
-            None => Err(PngError::ChunkNotFoundError.into()),
-        }
-    }
-}
+        png.append_chunk(
+            Chunk::from_strings("RuSt", "This is where your secret message will be!").unwrap(),
+        );
+        fs::write(file_name(), png.as_bytes()).unwrap();
 
-impl RemoveArgs {
-    pub fn remove(&self) -> Result<Chunk> {
-        let buffer = fs::read(&self.file_path)?;
-        let mut png = Png::try_from(&buffer[..])?;
-        let removed_chunk = png.remove_chunk(&self.chunk_type);
+        for crc in [2882656334, parse_crc("0xABD1D84E").unwrap()] {
+            let matches = FindCrcArgs {
+                file_path: file_name(),
+                crc,
+            }
+            .find_crc()
+            .unwrap();
 
-        if png.chunks().is_empty() {
-            fs::remove_file(&self.file_path).unwrap();
-        } else if removed_chunk.is_ok() {
-            fs::write(&self.file_path, &png.as_bytes()[..]).unwrap();
+            assert_eq!(matches, vec![(3, String::from("RuSt"))]);
         }
 
-        removed_chunk
+        fs::remove_file(file_name()).unwrap();
     }
-}
 
-impl PrintArgs {
-    pub fn print(&self) -> Result<String> {
-        let buffer = fs::read(&self.file_path)?;
-
-        Ok(Png::try_from(&buffer[..])?.to_string())
+    #[test]
+    fn test_parse_crc() {
+        assert_eq!(parse_crc("2882656334").unwrap(), 2882656334);
+        assert_eq!(parse_crc("0xabd1d84e").unwrap(), 2882656334);
+        assert!(parse_crc("0xRuSt").is_err());
+        assert!(parse_crc("-1").is_err());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{chunk::Chunk, chunk_type::ChunkType, png::Png};
-    use std::{
-        fs::{self, File},
-        str::FromStr,
-    };
+    #[test]
+    fn test_touch_messy_file() {
+        let ihdr_data = vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0];
+        let ihdr = Chunk::new(ChunkType::from_str("IHDR").unwrap(), ihdr_data);
+        let idat = Chunk::from_strings("IDAT", "").unwrap();
+        let gama = Chunk::from_strings("gAMA", "\0\0\0\0").unwrap();
+        let iend = Chunk::from_strings("IEND", "").unwrap();
+        let mut bytes =
+            Png::from_chunks(vec![ihdr.clone(), idat.clone(), gama.clone(), iend.clone()])
+                .as_bytes();
+        let gama_crc_end = 8 + (12 + 13) + 12 + (12 + 4);
 
-    /*
-        since these tests involve file manipulation, either each test has to work with a different
-        file or the tests must not be run concurrently to avoid unexpected behaviour
+        bytes[gama_crc_end - 1] ^= 1;
+        fs::write(file_name(), &bytes).unwrap();
 
-        in this case, the tests are run with "cargo test -- --test-threads=1"
-    */
+        let touch_args = TouchArgs {
+            file_path: file_name(),
+        };
+
+        assert_eq!(touch_args.touch().unwrap(), (1, true));
 
-    const FILE_NAME: &str = "test.png";
-    const OUTPUT_NAME: &str = "output.png";
-    const INVALID_FILE_NAME: &str = "invalid.png";
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_png_bytes_eq(&png, &Png::from_chunks(vec![ihdr, gama, idat, iend]));
+        assert!(verify::verify(&png, SpecVersion::default()).is_empty());
+        assert_eq!(touch_args.touch().unwrap(), (0, false));
+        fs::remove_file(file_name()).unwrap();
+    }
 
     #[test]
-    fn test_encode_empty_file() {
-        File::create(FILE_NAME).unwrap();
+    fn test_equal_ignoring_order() {
+        let mut chunks = testing_png_full().chunks().to_vec();
 
-        EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
-            message: String::from("I am the first chunk"),
-            output_file: None,
-        }
-        .encode()
-        .unwrap();
+        prepare_file(&file_name());
+        chunks.reverse();
+        fs::write(output_name(), Png::from_chunks(chunks).as_bytes()).unwrap();
+
+        let mut equal_args = EqualArgs {
+            first_path: file_name(),
+            second_path: output_name(),
+            ignore_order: false,
+        };
+
+        assert!(!equal_args.equal().unwrap());
+        equal_args.ignore_order = true;
+        assert!(equal_args.equal().unwrap());
 
-        let png_from_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
+        let mut png = testing_png_full();
 
-        assert_eq!(png_from_file.as_bytes(), testing_png_simple().as_bytes());
-        fs::remove_file(FILE_NAME).unwrap();
+        png.append_chunk(testing_chunk().unwrap());
+        fs::write(output_name(), png.as_bytes()).unwrap();
+        assert!(!equal_args.equal().unwrap());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
     }
 
     #[test]
-    fn test_encode_creates_new_file_if_not_exists() {
-        EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
-            message: String::from("I am the first chunk"),
-            output_file: None,
-        }
-        .encode()
-        .unwrap();
+    fn test_equal_identical_files() {
+        prepare_file(&file_name());
+        prepare_file(&output_name());
 
-        let png_from_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
+        let equal_args = EqualArgs {
+            first_path: file_name(),
+            second_path: output_name(),
+            ignore_order: false,
+        };
 
-        assert_eq!(png_from_file.as_bytes(), testing_png_simple().as_bytes());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert!(equal_args.equal().unwrap());
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
     }
 
     #[test]
-    fn test_encode_existing_file() {
-        prepare_file(FILE_NAME);
+    fn test_duplicates() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("tEXt", "Title\0First").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("tEXt", "Author\0Second").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("tEXt", "Comment\0Third").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
 
-        let new_chunk = testing_chunk().unwrap();
+        fs::write(file_name(), png.as_bytes()).unwrap();
 
-        EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: new_chunk.chunk_type().to_string(),
-            message: new_chunk.data_as_string().unwrap(),
-            output_file: None,
+        let duplicates = DuplicatesArgs {
+            file_path: file_name(),
         }
-        .encode()
+        .duplicates()
         .unwrap();
 
-        let png_from_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
-
         assert_eq!(
-            png_from_file.as_bytes(),
-            testing_png_full()
-                .as_bytes()
-                .iter()
-                .chain(new_chunk.as_bytes().iter())
-                .cloned()
-                .collect::<Vec<u8>>()
+            duplicates,
+            vec![
+                (ChunkType::from_str("tEXt").unwrap(), 3),
+                (ChunkType::from_str("IDAT").unwrap(), 2),
+            ]
         );
-        fs::remove_file(FILE_NAME).unwrap();
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_encode_empty_file_with_separate_output() {
-        File::create(FILE_NAME).unwrap();
-        EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
-            message: String::from("I am the first chunk"),
-            output_file: Some(String::from(OUTPUT_NAME)),
+    fn test_no_duplicates() {
+        prepare_file(&file_name());
+
+        let duplicates_args = DuplicatesArgs {
+            file_path: file_name(),
+        };
+
+        assert!(duplicates_args.duplicates().unwrap().is_empty());
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_seal_and_check_seal() {
+        prepare_file(&file_name());
+
+        SealArgs {
+            file_path: file_name(),
         }
-        .encode()
+        .seal()
         .unwrap();
-        assert!(fs::read(FILE_NAME).unwrap().is_empty());
 
-        let png_from_empty_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]);
-        let png_from_output_file = Png::try_from(&fs::read(OUTPUT_NAME).unwrap()[..]).unwrap();
+        let check_seal_args = CheckSealArgs {
+            file_path: file_name(),
+        };
 
-        assert!(png_from_empty_file.is_err());
-        assert_eq!(
-            png_from_output_file.as_bytes(),
-            testing_png_simple().as_bytes()
-        );
-        fs::remove_file(FILE_NAME).unwrap();
-        fs::remove_file(OUTPUT_NAME).unwrap();
+        assert!(check_seal_args.check_seal().unwrap());
+
+        let buffer = fs::read(file_name()).unwrap();
+        let mut png = Png::try_from(&buffer[..]).unwrap();
+
+        png.remove_chunk("miDl").unwrap();
+        png.insert_at_position(
+            Chunk::from_strings("miDl", "I have been tampered with").unwrap(),
+            Position::BeforeIend,
+        )
+        .unwrap();
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        assert!(!check_seal_args.check_seal().unwrap());
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_encode_existing_file_with_separate_output() {
-        prepare_file(FILE_NAME);
+    fn test_stream_order_flags_late_chunks() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("sRGB", "").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("gAMA", "").unwrap(),
+            Chunk::from_strings("tEXt", "Comment\0I am a comment").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
 
-        let new_chunk = testing_chunk().unwrap();
+        fs::write(file_name(), png.as_bytes()).unwrap();
 
-        EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: new_chunk.chunk_type().to_string(),
-            message: new_chunk.data_as_string().unwrap(),
-            output_file: Some(String::from(OUTPUT_NAME)),
+        let order = StreamOrderArgs {
+            file_path: file_name(),
         }
-        .encode()
+        .stream_order()
         .unwrap();
 
-        let png_from_input_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
-        let png_from_output_file = Png::try_from(&fs::read(OUTPUT_NAME).unwrap()[..]).unwrap();
-
+        assert_eq!(order.len(), 6);
+        assert_eq!(order[1], (1, String::from("sRGB"), None));
         assert_eq!(
-            png_from_input_file.as_bytes(),
-            testing_png_full().as_bytes()
+            order[3],
+            (3, String::from("gAMA"), Some(String::from("IDAT")))
         );
+        assert!(order
+            .iter()
+            .filter(|(i, ..)| *i != 3)
+            .all(|(_, _, late)| late.is_none()));
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_text() {
+        let mut ztxt_data = b"Comment\0\0".to_vec();
+
+        ztxt_data.extend(compression::deflate(b"I am a compressed comment").unwrap());
+
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("tEXt", "Title\0I am a title").unwrap(),
+            Chunk::from_strings("RuSt", "I am not a text chunk").unwrap(),
+            Chunk::new(ChunkType::from_str("zTXt").unwrap(), ztxt_data),
+        ]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let text = TextArgs {
+            file_path: file_name(),
+        }
+        .text()
+        .unwrap();
+
         assert_eq!(
-            png_from_output_file.as_bytes(),
-            testing_png_full()
-                .as_bytes()
-                .iter()
-                .chain(new_chunk.as_bytes().iter())
-                .cloned()
-                .collect::<Vec<u8>>()
+            text,
+            vec![
+                (String::from("Title"), String::from("I am a title")),
+                (
+                    String::from("Comment"),
+                    String::from("I am a compressed comment")
+                ),
+            ]
         );
-        fs::remove_file(FILE_NAME).unwrap();
-        fs::remove_file(OUTPUT_NAME).unwrap();
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_encode_chunk_type_too_long() {
-        let result = EncodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("abcdefg"),
-            message: String::from("My chunk type is invalid"),
-            output_file: None,
+    fn test_crc_debug() {
+        let mut bytes = testing_png_full().as_bytes();
+        let last = bytes.len() - 1;
+
+        // corrupt the crc of the last chunk
+        bytes[last] ^= 0xff;
+        fs::write(file_name(), &bytes).unwrap();
+
+        let chunk = Chunk::from_strings("LASt", "I am the last chunk").unwrap();
+        let output = CrcDebugArgs {
+            file_path: file_name(),
+            chunk_type: String::from("LASt"),
         }
-        .encode();
+        .crc_debug()
+        .unwrap();
 
-        assert!(result.is_err());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert!(output.starts_with(&format!("Input ({} bytes): 4c 41 53 74 49 ", 4 + 19)));
+        assert!(output.contains(&format!("Calculated CRC: {:#010x}\n", chunk.crc())));
+        assert!(output.ends_with(&format!("Stored CRC: {:#010x}\n", chunk.crc() ^ 0xff)));
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_decode_existing_file() {
-        prepare_file(FILE_NAME);
+    fn test_clean() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("IHDR", "").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+        let mut bytes = png.as_bytes();
 
-        let decode_args = DecodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+        bytes.extend(testing_chunk().unwrap().as_bytes());
+        bytes.extend(b"I am some junk");
+        fs::write(file_name(), &bytes).unwrap();
+
+        let clean_args = CleanArgs {
+            file_path: file_name(),
         };
 
-        assert_eq!(decode_args.decode().unwrap(), "I am the first chunk");
-        fs::remove_file(FILE_NAME).unwrap();
+        assert_eq!(clean_args.clean().unwrap(), 29 + 14);
+        assert_eq!(fs::read(file_name()).unwrap(), png.as_bytes());
+        assert_eq!(clean_args.clean().unwrap(), 0);
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_decode_does_not_modify_input_file() {
-        prepare_file(FILE_NAME);
+    fn test_list() {
+        prepare_file(&file_name());
 
-        DecodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+        let chunk_types = ListArgs {
+            file_path: file_name(),
         }
-        .decode()
+        .list()
+        .unwrap();
+
+        assert_eq!(chunk_types, ["FrSt", "miDl", "LASt"]);
+        fs::remove_file(file_name()).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_and_build() {
+        let manifest_name = &test_path("manifest.json");
+
+        fs::write(file_name(), crate::test_utils::PNG_FILE).unwrap();
+
+        let manifest = ManifestArgs {
+            file_path: file_name(),
+        }
+        .manifest()
         .unwrap();
 
-        let png_from_input_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
+        fs::write(manifest_name, manifest).unwrap();
+
+        let chunk_count = BuildArgs {
+            manifest_path: String::from(manifest_name),
+            output_file: output_name(),
+        }
+        .build()
+        .unwrap();
 
         assert_eq!(
-            png_from_input_file.as_bytes(),
-            testing_png_full().as_bytes()
+            chunk_count,
+            Png::try_from(&crate::test_utils::PNG_FILE[..])
+                .unwrap()
+                .chunks()
+                .len()
+        );
+        assert_eq!(
+            fs::read(output_name()).unwrap(),
+            crate::test_utils::PNG_FILE
         );
-        fs::remove_file(FILE_NAME).unwrap();
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
+        fs::remove_file(manifest_name).unwrap();
     }
 
     #[test]
-    fn test_decode_non_existing_file() {
-        let decode_args = DecodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+    fn test_color() {
+        let ihdr_data = [0, 0, 0, 50, 0, 0, 0, 50, 8, 6, 0, 0, 0].to_vec();
+        let png = Png::from_chunks(vec![Chunk::new(
+            ChunkType::from_str("IHDR").unwrap(),
+            ihdr_data,
+        )]);
+
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let color_args = ColorArgs {
+            file_path: file_name(),
         };
 
-        assert!(decode_args.decode().is_err());
+        assert_eq!(color_args.color().unwrap(), "RGBA, 8-bit, 4 channels");
+
+        prepare_file(&file_name());
+
+        assert!(matches!(
+            color_args.color().err().unwrap().downcast_ref(),
+            Some(IhdrError::MissingIhdr)
+        ));
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_decode_invalid_file() {
-        File::create(INVALID_FILE_NAME).unwrap();
-
-        let decode_args = DecodeArgs {
-            file_path: String::from(INVALID_FILE_NAME),
-            chunk_type: String::from("FrSt"),
+    fn test_diff_backup() {
+        let diff_backup_args = DiffBackupArgs {
+            file_path: file_name(),
         };
 
-        assert!(decode_args.decode().is_err());
-        fs::remove_file(INVALID_FILE_NAME).unwrap();
-    }
+        prepare_file(&file_name());
 
-    #[test]
-    fn test_decode_valid_file_without_required_chunk() {
-        prepare_file(FILE_NAME);
+        assert!(matches!(
+            diff_backup_args.diff_backup().err().unwrap().downcast_ref(),
+            Some(DiffError::MissingBackup(_))
+        ));
 
-        let decode_args = DecodeArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("TeSt"),
-        };
+        fs::copy(file_name(), diff::backup_path(&file_name())).unwrap();
 
-        assert!(decode_args.decode().is_err());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert!(diff_backup_args.diff_backup().unwrap().is_empty());
+
+        let mut png = testing_png_full();
+
+        png.remove_chunk("miDl").unwrap();
+        png.append_chunk(testing_chunk().unwrap());
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        assert_eq!(
+            diff_backup_args.diff_backup().unwrap(),
+            ["- Chunk 1: miDl (18 bytes)", "+ Chunk 2: TeSt (17 bytes)"]
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(diff::backup_path(&file_name())).unwrap();
     }
 
     #[test]
-    fn test_remove_existing_file() {
-        prepare_file(FILE_NAME);
+    fn test_validate() {
+        prepare_file(&file_name());
 
-        let remove_args = RemoveArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+        let validate_args = ValidateArgs {
+            file_path: file_name(),
         };
-        let removed_chunk = remove_args.remove().unwrap();
-        let testing_chunk = chunk_from_strings("FrSt", "I am the first chunk").unwrap();
+        let report = validate_args.validate().unwrap();
 
-        assert_eq!(removed_chunk.as_bytes(), testing_chunk.as_bytes());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert_eq!(report.len(), 3);
+        assert!(report.iter().all(|(_, valid)| *valid));
+
+        let mut bytes = testing_png_full().as_bytes();
+        let last = bytes.len() - 1;
+
+        bytes[last] ^= 1;
+        fs::write(file_name(), bytes).unwrap();
+
+        let report = validate_args.validate().unwrap();
+
+        assert_eq!(report[2], (ChunkType::from_str("LASt").unwrap(), false));
+        assert!(report[..2].iter().all(|(_, valid)| *valid));
+
+        fs::write(file_name(), [0; 8]).unwrap();
+
+        assert!(matches!(
+            validate_args.validate().err().unwrap().downcast_ref(),
+            Some(PngError::InvalidHeaderError)
+        ));
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_remove_does_modify_input_file() {
-        prepare_file(FILE_NAME);
+    fn test_reorder() {
+        let config_name = test_path("order.toml");
 
-        let remove_args = RemoveArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+        prepare_file(&file_name());
+        fs::write(&config_name, r#"order = ["LASt", "*", "FrSt"]"#).unwrap();
+
+        let reorder_args = ReorderArgs {
+            file_path: file_name(),
+            config: config_name.clone(),
         };
-        let mut png = testing_png_full();
 
-        remove_args.remove().unwrap();
-        png.remove_chunk("FrSt").unwrap();
+        assert!(reorder_args.reorder().unwrap());
+        assert!(!reorder_args.reorder().unwrap());
 
-        let png_from_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
+        let chunks = testing_png_full().chunks().to_vec();
 
-        assert_eq!(png.as_bytes(), png_from_file.as_bytes());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert_eq!(
+            fs::read(file_name()).unwrap(),
+            Png::from_chunks(vec![
+                chunks[2].clone(),
+                chunks[1].clone(),
+                chunks[0].clone()
+            ])
+            .as_bytes()
+        );
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(&config_name).unwrap();
     }
 
     #[test]
-    fn test_remove_non_existing_file() {
-        let remove_args = RemoveArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+    fn test_capacity_report() {
+        let dir_name = test_path("capacity_report");
+
+        let sub_dir = Path::new(&dir_name).join("nested");
+        let mut png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 13]),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
+
+        fs::create_dir_all(&sub_dir).unwrap();
+        fs::write(Path::new(&dir_name).join("plain.png"), png.as_bytes()).unwrap();
+        fs::write(Path::new(&dir_name).join("notes.txt"), "not a png").unwrap();
+
+        // one file is watermarked with a custom chunk, the other with bytes after IEND
+        png.insert_before_end(testing_chunk().unwrap());
+        fs::write(Path::new(&dir_name).join("chunk.png"), png.as_bytes()).unwrap();
+        png.remove_chunk("TeSt").unwrap();
+        fs::write(
+            sub_dir.join("trailing.PNG"),
+            [png.as_bytes(), b"hidden".to_vec()].concat(),
+        )
+        .unwrap();
+
+        let capacity_args = |recursive| CapacityReportArgs {
+            dir_path: dir_name.clone(),
+            recursive,
         };
+        let path = |name: &str| Path::new(&dir_name).join(name).display().to_string();
 
-        assert!(remove_args.remove().is_err());
+        assert_eq!(
+            capacity_args(false).capacity_report().unwrap(),
+            [(path("chunk.png"), Ok(17)), (path("plain.png"), Ok(0))]
+        );
+        assert_eq!(
+            capacity_args(true).capacity_report().unwrap(),
+            [
+                (path("chunk.png"), Ok(17)),
+                (path("nested/trailing.PNG"), Ok(6)),
+                (path("plain.png"), Ok(0))
+            ]
+        );
+        fs::remove_dir_all(&dir_name).unwrap();
     }
 
     #[test]
-    fn test_remove_invalid_file() {
-        File::create(INVALID_FILE_NAME).unwrap();
+    fn test_capacity_report_skips_corrupt_file() {
+        let dir_name = test_path("capacity_report");
+        let sub_dir = Path::new(&dir_name).join("nested");
+        let mut png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 13]),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ]);
 
-        let remove_args = RemoveArgs {
-            file_path: String::from(INVALID_FILE_NAME),
-            chunk_type: String::from("FrSt"),
-        };
+        png.insert_before_end(testing_chunk().unwrap());
+        fs::create_dir_all(&sub_dir).unwrap();
+        fs::write(Path::new(&dir_name).join("chunk.png"), png.as_bytes()).unwrap();
+        fs::write(sub_dir.join("corrupt.png"), b"I am not a png").unwrap();
 
-        assert!(remove_args.remove().is_err());
-        fs::remove_file(INVALID_FILE_NAME).unwrap();
+        let report = CapacityReportArgs {
+            dir_path: dir_name.clone(),
+            recursive: true,
+        }
+        .capacity_report()
+        .unwrap();
+        let path = |name: &str| Path::new(&dir_name).join(name).display().to_string();
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0], (path("chunk.png"), Ok(17)));
+        assert_eq!(report[1].0, path("nested/corrupt.png"));
+        assert!(report[1].1.is_err());
+        fs::remove_dir_all(&dir_name).unwrap();
     }
 
     #[test]
-    fn test_remove_valid_file_without_required_chunk() {
-        prepare_file(FILE_NAME);
+    fn test_extract() {
+        let payload = (0..=255).collect::<Vec<u8>>();
+        let mut png = testing_png_full();
 
-        let remove_args = RemoveArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("TeSt"),
+        png.append_chunk(Chunk::new(
+            ChunkType::from_str("ruSt").unwrap(),
+            payload.clone(),
+        ));
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let extract_args = |chunk_type: &str| ExtractArgs {
+            file_path: file_name(),
+            chunk_type: String::from(chunk_type),
+            output_path: data_name(),
         };
-        let result = remove_args.remove();
-        let png_from_file = Png::try_from(&fs::read(FILE_NAME).unwrap()[..]).unwrap();
 
-        assert!(result.is_err());
-        assert_eq!(png_from_file.as_bytes(), testing_png_full().as_bytes());
-        fs::remove_file(FILE_NAME).unwrap();
+        assert_eq!(extract_args("ruSt").extract().unwrap(), 256);
+        assert_eq!(fs::read(data_name()).unwrap(), payload);
+        fs::remove_file(data_name()).unwrap();
+
+        assert!(matches!(
+            extract_args("TeSt").extract().err().unwrap().downcast_ref(),
+            Some(PngError::ChunkNotFoundError)
+        ));
+        assert!(!Path::new(&data_name()).exists());
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_remove_deletes_file_after_removing_last_chunk() {
-        File::create(FILE_NAME).unwrap();
-        fs::write(FILE_NAME, testing_png_simple().as_bytes()).unwrap();
+    fn test_get_icc() {
+        let profile = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let mut png = testing_png_full();
 
-        let remove_args = RemoveArgs {
-            file_path: String::from(FILE_NAME),
-            chunk_type: String::from("FrSt"),
+        png.insert_chunk(
+            0,
+            Chunk::new(
+                ChunkType::from_str("iCCP").unwrap(),
+                [
+                    &b"Synthetic\0\0"[..],
+                    &compression::deflate(&profile).unwrap(),
+                ]
+                .concat(),
+            ),
+        );
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let get_icc_args = GetIccArgs {
+            file_path: file_name(),
+            output_path: data_name(),
         };
 
-        remove_args.remove().unwrap();
-        assert!(File::open(FILE_NAME).is_err());
+        assert_eq!(get_icc_args.get_icc().unwrap().name, "Synthetic");
+        assert_eq!(fs::read(data_name()).unwrap(), profile);
+        fs::remove_file(data_name()).unwrap();
+
+        prepare_file(&file_name());
+
+        assert!(matches!(
+            get_icc_args.get_icc().err().unwrap().downcast_ref(),
+            Some(PngError::ChunkNotFoundError)
+        ));
+        fs::remove_file(file_name()).unwrap();
     }
 
     #[test]
-    fn test_print_existing_file() {
-        prepare_file(FILE_NAME);
+    fn test_set_icc_round_trip() {
+        let profile = (0..=255).rev().cycle().take(1000).collect::<Vec<u8>>();
 
-        let print_args = PrintArgs {
-            file_path: String::from(FILE_NAME),
+        prepare_file(&file_name());
+        fs::write(data_name(), &profile).unwrap();
+
+        let set_icc_args = |name: &str| SetIccArgs {
+            file_path: file_name(),
+            profile_path: data_name(),
+            name: name.to_string(),
         };
 
-        assert_eq!(print_args.print().unwrap(), testing_png_full().to_string());
-        fs::remove_file(FILE_NAME).unwrap();
+        set_icc_args("Old").set_icc().unwrap();
+        set_icc_args("sRGB").set_icc().unwrap();
+
+        let png = Png::try_from(&fs::read(file_name()).unwrap()[..]).unwrap();
+
+        assert_eq!(png.chunks_by_type("iCCP").len(), 1);
+        assert_eq!(png.chunks()[1].chunk_type().to_string(), "iCCP");
+
+        let get_icc_args = GetIccArgs {
+            file_path: file_name(),
+            output_path: output_name(),
+        };
+
+        assert_eq!(get_icc_args.get_icc().unwrap().name, "sRGB");
+        assert_eq!(fs::read(output_name()).unwrap(), profile);
+        assert!(matches!(
+            set_icc_args("").set_icc().err().unwrap().downcast_ref(),
+            Some(IccError::InvalidNameLength(0))
+        ));
+        fs::remove_file(file_name()).unwrap();
+        fs::remove_file(data_name()).unwrap();
+        fs::remove_file(output_name()).unwrap();
     }
 
     #[test]
-    fn test_print_non_existing_file() {
-        let print_args = PrintArgs {
-            file_path: String::from(FILE_NAME),
+    fn test_bash_completions() {
+        let script = CompletionsArgs { shell: Shell::Bash }
+            .completions()
+            .unwrap();
+
+        assert!(script.contains("encode"));
+        assert!(script.contains("decode"));
+        assert!(script.contains("completions"));
+    }
+
+    #[cfg(feature = "decode-check")]
+    #[test]
+    fn test_decode_check_after_encoding() {
+        let mut png = Png::try_from(&crate::test_utils::PNG_FILE[..]).unwrap();
+
+        png.insert_before_end(testing_chunk().unwrap());
+        fs::write(file_name(), png.as_bytes()).unwrap();
+
+        let decode_check_args = DecodeCheckArgs {
+            file_path: file_name(),
         };
 
-        assert!(print_args.print().is_err());
+        assert_eq!(decode_check_args.decode_check().unwrap(), (50, 50));
+        fs::remove_file(file_name()).unwrap();
     }
 
+    #[cfg(feature = "decode-check")]
     #[test]
-    fn test_print_invalid_file() {
-        File::create(INVALID_FILE_NAME).unwrap();
+    fn test_decode_check_without_image_data() {
+        prepare_file(&file_name());
 
-        let print_args = PrintArgs {
-            file_path: String::from(INVALID_FILE_NAME),
+        let decode_check_args = DecodeCheckArgs {
+            file_path: file_name(),
         };
 
-        assert!(print_args.print().is_err());
-        fs::remove_file(INVALID_FILE_NAME).unwrap();
+        assert!(decode_check_args.decode_check().is_err());
+        fs::remove_file(file_name()).unwrap();
     }
 
     fn prepare_file(file_name: &str) {
         let png = testing_png_full();
 
-        fs::write(file_name, &png.as_bytes()).unwrap();
+        fs::write(file_name, png.as_bytes()).unwrap();
     }
 
     fn testing_chunk() -> Result<Chunk> {
-        chunk_from_strings("TeSt", "I am a test chunk")
-    }
-
-    fn chunk_from_strings(chunk_type: &str, data: &str) -> Result<Chunk> {
-        let chunk_type = ChunkType::from_str(chunk_type)?;
-        let data: Vec<u8> = data.bytes().collect();
-
-        Ok(Chunk::new(chunk_type, data))
+        Chunk::from_strings("TeSt", "I am a test chunk")
     }
 
     fn testing_png_simple() -> Png {
-        let chunks = vec![chunk_from_strings("FrSt", "I am the first chunk").unwrap()];
+        let chunks = vec![Chunk::from_strings("FrSt", "I am the first chunk").unwrap()];
 
         Png::from_chunks(chunks)
     }
 
     fn testing_png_full() -> Png {
         let chunks = vec![
-            chunk_from_strings("FrSt", "I am the first chunk").unwrap(),
-            chunk_from_strings("miDl", "I am another chunk").unwrap(),
-            chunk_from_strings("LASt", "I am the last chunk").unwrap(),
+            Chunk::from_strings("FrSt", "I am the first chunk").unwrap(),
+            Chunk::from_strings("miDl", "I am another chunk").unwrap(),
+            Chunk::from_strings("LASt", "I am the last chunk").unwrap(),
         ];
 
         Png::from_chunks(chunks)
@@ -1,12 +1,18 @@
-use crate::chunk_type::{ChunkType, ChunkTypeError};
+use crate::{
+    chunk_type::{ChunkType, ChunkTypeError, ValidationOptions},
+    size,
+};
 use anyhow::Result;
 use crc::{Crc, CRC_32_ISO_HDLC};
 use std::{
     fmt::Display,
     io::{self, BufReader, Read},
+    str::FromStr,
+    string::FromUtf8Error,
 };
 use thiserror::Error;
 
+#[derive(Clone, PartialEq)]
 pub struct Chunk {
     length: u32,
     chunk_type: ChunkType,
@@ -22,27 +28,57 @@ pub enum ChunkError {
     MalformedChunk(#[from] io::Error),
     #[error("Invalid ChunkType: {0}")]
     InvalidChunkType(#[from] ChunkTypeError),
+    #[error("The data of the chunk is not valid UTF-8: {0}")]
+    NonUtf8Data(#[from] FromUtf8Error),
+    #[error("Found {0} unexpected bytes after the CRC of the chunk")]
+    TrailingBytes(usize),
+    #[error("A field of {0} bytes is declared, but only {1} bytes of data follow its length")]
+    TruncatedField(u32, usize),
+    #[error("The length field says {0} bytes, but the chunk holds {1} bytes of data")]
+    LengthMismatch(u32, usize),
 }
 
 impl Chunk {
     const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
 
     pub fn new(chunk_type: ChunkType, data: Vec<u8>) -> Self {
+        Self::from_parts(data.len() as u32, chunk_type, data)
+    }
+
+    /// Creates a chunk whose length field is the given one, even if it disagrees with the size of the data.
+    pub fn from_parts(length: u32, chunk_type: ChunkType, data: Vec<u8>) -> Self {
         let crc = Self::calculate_crc(&chunk_type, &data);
 
         Self {
-            length: data.len() as u32,
+            length,
             chunk_type,
             chunk_data: data,
             crc,
         }
     }
 
+    /// Creates a chunk from a chunk type and a message, both given as strings.
+    ///
+    /// ```
+    /// use pngme::chunk::Chunk;
+    ///
+    /// let chunk = Chunk::from_strings("RuSt", "This is a secret message").unwrap();
+    ///
+    /// assert_eq!(chunk.chunk_type().to_string(), "RuSt");
+    /// assert_eq!(chunk.data_as_string().unwrap(), "This is a secret message");
+    /// assert!(Chunk::from_strings("Rust!", "Invalid chunk type").is_err());
+    /// ```
+    pub fn from_strings(chunk_type: &str, data: &str) -> Result<Self> {
+        let chunk_type = ChunkType::from_str(chunk_type)?;
+
+        Ok(Self::new(chunk_type, data.as_bytes().to_vec()))
+    }
+
     pub fn length(&self) -> u32 {
         self.length
     }
 
-    fn crc(&self) -> u32 {
+    pub fn crc(&self) -> u32 {
         self.crc
     }
 
@@ -50,12 +86,26 @@ impl Chunk {
         &self.chunk_type
     }
 
-    fn data(&self) -> &[u8] {
+    pub fn data(&self) -> &[u8] {
         &self.chunk_data
     }
 
     pub fn data_as_string(&self) -> Result<String> {
-        String::from_utf8(self.chunk_data.clone()).map_err(|e| e.into())
+        String::from_utf8(self.chunk_data.clone()).map_err(|e| ChunkError::NonUtf8Data(e).into())
+    }
+
+    /// Splits the data into a field prefixed by its big-endian u32 length and the rest after it.
+    pub fn read_length_prefixed(&self) -> Result<(&[u8], &[u8])> {
+        let (length, rest) = self
+            .chunk_data
+            .split_first_chunk::<4>()
+            .ok_or(ChunkError::TruncatedField(4, self.chunk_data.len()))?;
+        let length = u32::from_be_bytes(*length);
+
+        usize::try_from(length)
+            .ok()
+            .and_then(|field_length| rest.split_at_checked(field_length))
+            .ok_or_else(|| ChunkError::TruncatedField(length, rest.len()).into())
     }
 
     pub fn as_bytes(&self) -> Vec<u8> {
@@ -70,33 +120,44 @@ impl Chunk {
             .collect::<Vec<u8>>()
     }
 
-    fn calculate_crc(chunk_type: &ChunkType, data: &[u8]) -> u32 {
-        /*
-            from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-layout
-            and https://reveng.sourceforge.io/crc-catalogue/all.htm
+    pub fn has_valid_crc(&self) -> bool {
+        self.crc == self.calculated_crc()
+    }
 
-            the crc is calculated on the bytes of the chunk type and data, and it needs to be 4 bytes long
-        */
-        Self::CRC.checksum(&[&chunk_type.bytes()[..], data].concat())
+    /// The bytes the CRC is calculated on, which are the chunk type followed by the data.
+    pub fn crc_input(&self) -> Vec<u8> {
+        [&self.chunk_type.bytes()[..], &self.chunk_data].concat()
     }
-}
 
-impl Display for Chunk {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Chunk {{",)?;
-        writeln!(f, "  Length: {}", self.length())?;
-        writeln!(f, "  Type: {}", self.chunk_type())?;
-        writeln!(f, "  Data: {} bytes", self.data().len())?;
-        writeln!(f, "  Crc: {}", self.crc())?;
-        writeln!(f, "}}",)?;
+    /// The CRC calculated again from the chunk type and data, which may differ from the stored one.
+    pub fn calculated_crc(&self) -> u32 {
+        Self::calculate_crc(&self.chunk_type, &self.chunk_data)
+    }
+
+    /// Checks the chunk type, the length field and the CRC of a chunk, however it was built.
+    pub fn validate(&self) -> Result<(), ChunkError> {
+        self.chunk_type.validate(&ValidationOptions::default())?;
+
+        if self.length as usize != self.chunk_data.len() {
+            return Err(ChunkError::LengthMismatch(
+                self.length,
+                self.chunk_data.len(),
+            ));
+        }
+
+        if !self.has_valid_crc() {
+            return Err(ChunkError::InvalidChecksumError);
+        }
+
         Ok(())
     }
-}
 
-impl TryFrom<&[u8]> for Chunk {
-    type Error = ChunkError;
+    pub fn try_from_unchecked(value: &[u8]) -> Result<Self, ChunkError> {
+        // the stored crc is kept as it is, even when it doesn't match the data
+        Self::read(value, false)
+    }
 
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+    fn read(value: &[u8], verify_crc: bool) -> Result<Self, ChunkError> {
         /*
             a slice of u8 (byte) interpreted as a png chunk is structured as follows:
             - first 4 bytes: length (n)
@@ -121,8 +182,14 @@ impl TryFrom<&[u8]> for Chunk {
         input_stream.read_exact(&mut buffer_4_bytes)?;
 
         let input_crc = u32::from_be_bytes(buffer_4_bytes);
+        // the slice must hold exactly one chunk, anything left over is not part of it
+        let trailing_bytes = value.len() - (12 + chunk_data.len());
+
+        if trailing_bytes > 0 {
+            return Err(ChunkError::TrailingBytes(trailing_bytes));
+        }
 
-        if input_crc != Self::calculate_crc(&chunk_type, &chunk_data) {
+        if verify_crc && input_crc != Self::calculate_crc(&chunk_type, &chunk_data) {
             return Err(ChunkError::InvalidChecksumError);
         }
 
@@ -133,13 +200,59 @@ impl TryFrom<&[u8]> for Chunk {
             crc: input_crc,
         })
     }
+
+    pub(crate) fn calculate_crc(chunk_type: &ChunkType, data: &[u8]) -> u32 {
+        /*
+            from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-layout
+            and https://reveng.sourceforge.io/crc-catalogue/all.htm
+
+            the crc is calculated on the bytes of the chunk type and data, and it needs to be 4 bytes long
+        */
+        Self::CRC.checksum(&[&chunk_type.bytes()[..], data].concat())
+    }
+}
+
+impl Display for Chunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Chunk {{",)?;
+
+        // the alternate flag ({:#}) prints the sizes in human-readable units
+        if f.alternate() {
+            writeln!(
+                f,
+                "  Length: {}",
+                size::human_readable(self.length() as u64)
+            )?;
+            writeln!(f, "  Type: {}", self.chunk_type())?;
+            writeln!(
+                f,
+                "  Data: {}",
+                size::human_readable(self.data().len() as u64)
+            )?;
+        } else {
+            writeln!(f, "  Length: {}", self.length())?;
+            writeln!(f, "  Type: {}", self.chunk_type())?;
+            writeln!(f, "  Data: {} bytes", self.data().len())?;
+        }
+
+        writeln!(f, "  Crc: {}", self.crc())?;
+        writeln!(f, "}}",)?;
+        Ok(())
+    }
+}
+
+impl TryFrom<&[u8]> for Chunk {
+    type Error = ChunkError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::read(value, true)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::chunk_type::ChunkType;
-    use std::str::FromStr;
 
     #[test]
     fn test_new_chunk() {
@@ -167,6 +280,55 @@ mod tests {
         assert_eq!(chunk.chunk_type().to_string(), String::from("RuSt"));
     }
 
+    #[test]
+    fn test_chunk_data_and_crc() {
+        let data = [0, 159, 255, 10];
+        let chunk = Chunk::new(ChunkType::from_str("RuSt").unwrap(), data.to_vec());
+
+        assert_eq!(chunk.data(), &data[..]);
+        assert_ne!(chunk.crc(), 0);
+    }
+
+    #[test]
+    fn test_validate_chunk() {
+        assert!(testing_chunk().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_chunk_bad_crc() {
+        let mut chunk_bytes = testing_chunk().as_bytes();
+        let last = chunk_bytes.len() - 1;
+
+        chunk_bytes[last] ^= 1;
+
+        assert!(matches!(
+            Chunk::try_from_unchecked(&chunk_bytes).unwrap().validate(),
+            Err(ChunkError::InvalidChecksumError)
+        ));
+    }
+
+    #[test]
+    fn test_validate_chunk_length_mismatch() {
+        let chunk = Chunk::from_parts(100, ChunkType::from_str("RuSt").unwrap(), vec![1, 2, 3]);
+
+        assert!(matches!(
+            chunk.validate(),
+            Err(ChunkError::LengthMismatch(100, 3))
+        ));
+    }
+
+    #[test]
+    fn test_validate_chunk_reserved_bit_set() {
+        let chunk = Chunk::new(ChunkType::from_str("Rust").unwrap(), vec![]);
+
+        assert!(matches!(
+            chunk.validate(),
+            Err(ChunkError::InvalidChunkType(
+                ChunkTypeError::ReservedBitSet(_)
+            ))
+        ));
+    }
+
     #[test]
     fn test_chunk_string() {
         let chunk = testing_chunk();
@@ -187,7 +349,7 @@ mod tests {
     fn test_chunk_data_as_string_invalid() {
         let data_length: u32 = 1;
         let chunk_type = "RuSt".as_bytes();
-        let message_bytes = vec![0x81];
+        let message_bytes = [0x81];
         let crc: u32 = 1728488629;
         let chunk_data: Vec<u8> = data_length
             .to_be_bytes()
@@ -226,6 +388,85 @@ mod tests {
         assert_eq!(chunk.crc(), 2882656334);
     }
 
+    #[test]
+    fn test_chunk_from_bytes_unchecked_keeps_invalid_crc() {
+        let mut chunk_bytes =
+            Chunk::from_strings("RuSt", "This is where your secret message will be!")
+                .unwrap()
+                .as_bytes();
+        let last = chunk_bytes.len() - 1;
+
+        chunk_bytes[last] ^= 1;
+
+        let chunk = Chunk::try_from_unchecked(&chunk_bytes).unwrap();
+
+        assert!(Chunk::try_from(&chunk_bytes[..]).is_err());
+        assert!(!chunk.has_valid_crc());
+        assert_eq!(chunk.crc(), 2882656334 ^ 1);
+        assert_eq!(chunk.as_bytes(), chunk_bytes);
+    }
+
+    #[test]
+    fn test_chunk_from_bytes_trailing_bytes() {
+        let mut chunk_bytes =
+            Chunk::from_strings("RuSt", "This is where your secret message will be!")
+                .unwrap()
+                .as_bytes();
+
+        chunk_bytes.push(0);
+
+        assert!(matches!(
+            Chunk::try_from(&chunk_bytes[..]),
+            Err(ChunkError::TrailingBytes(1))
+        ));
+    }
+
+    #[test]
+    fn test_read_length_prefixed() {
+        let chunk = Chunk::new(
+            ChunkType::from_str("RuSt").unwrap(),
+            [&[0, 0, 0, 4][..], b"name", b"the rest"].concat(),
+        );
+
+        assert_eq!(
+            chunk.read_length_prefixed().unwrap(),
+            (&b"name"[..], &b"the rest"[..])
+        );
+
+        let empty_field = Chunk::new(ChunkType::from_str("RuSt").unwrap(), vec![0, 0, 0, 0]);
+
+        assert_eq!(
+            empty_field.read_length_prefixed().unwrap(),
+            (&b""[..], &b""[..])
+        );
+    }
+
+    #[test]
+    fn test_read_length_prefixed_truncated() {
+        let truncated_field = Chunk::new(
+            ChunkType::from_str("RuSt").unwrap(),
+            [&[0, 0, 0, 10][..], b"name"].concat(),
+        );
+        let truncated_length = Chunk::new(ChunkType::from_str("RuSt").unwrap(), vec![0, 0]);
+
+        assert!(matches!(
+            truncated_field
+                .read_length_prefixed()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(ChunkError::TruncatedField(10, 4))
+        ));
+        assert!(matches!(
+            truncated_length
+                .read_length_prefixed()
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(ChunkError::TruncatedField(4, 2))
+        ));
+    }
+
     #[test]
     fn test_chunk_from_bytes_invalid_length() {
         assert!(Chunk::try_from(b"0".as_ref()).is_err());
@@ -263,6 +504,22 @@ mod tests {
         assert!(Chunk::try_from(chunk_data.as_ref()).is_err());
     }
 
+    #[test]
+    fn test_chunk_from_bytes_header_only() {
+        let data_length: u32 = 42;
+        let chunk_data: Vec<u8> = data_length
+            .to_be_bytes()
+            .iter()
+            .chain(b"RuSt".iter())
+            .copied()
+            .collect();
+
+        assert!(matches!(
+            Chunk::try_from(chunk_data.as_ref()),
+            Err(ChunkError::MalformedChunk(_))
+        ));
+    }
+
     #[test]
     fn test_chunk_from_bytes_invalid_crc_size() {
         let data_length: u32 = 42;
@@ -318,6 +575,16 @@ mod tests {
         let _chunk_string = format!("{}", chunk);
     }
 
+    #[test]
+    fn test_chunk_display_human_readable() {
+        let chunk = Chunk::new(ChunkType::from_str("RuSt").unwrap(), vec![0; 1536]);
+        let chunk_string = format!("{:#}", chunk);
+
+        assert!(chunk_string.contains("  Length: 1.5 KiB\n"));
+        assert!(chunk_string.contains("  Data: 1.5 KiB\n"));
+        assert!(chunk.to_string().contains("  Data: 1536 bytes\n"));
+    }
+
     fn testing_chunk() -> Chunk {
         let data_length: u32 = 42;
         let chunk_type = "RuSt".as_bytes();
@@ -334,4 +601,14 @@ mod tests {
 
         Chunk::try_from(chunk_data.as_ref()).unwrap()
     }
+
+    #[test]
+    fn test_chunk_non_utf8_data_as_string() {
+        let chunk = Chunk::new(ChunkType::from_str("RuSt").unwrap(), vec![0xff, 0xfe]);
+
+        assert!(matches!(
+            chunk.data_as_string().err().unwrap().downcast_ref(),
+            Some(ChunkError::NonUtf8Data(_))
+        ));
+    }
 }
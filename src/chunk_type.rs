@@ -1,7 +1,7 @@
 use std::{fmt::Display, str, str::FromStr};
 use thiserror::Error;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ChunkType {
     bytes: [u8; 4],
 }
@@ -12,6 +12,14 @@ pub enum ChunkTypeError {
     NonAlphabeticCharacters(Vec<u8>),
     #[error("The string must be 4 characters long, found {0}")]
     InvalidStringLength(usize),
+    #[error("The reserved bit of the chunk type {0} is set, its third letter must be uppercase")]
+    ReservedBitSet(String),
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ValidationOptions {
+    /// Accept chunk types with the reserved bit set, as used by some experimental formats
+    pub allow_reserved_bit_set: bool,
 }
 
 impl ChunkType {
@@ -19,7 +27,7 @@ impl ChunkType {
         self.bytes
     }
 
-    fn is_critical(&self) -> bool {
+    pub fn is_critical(&self) -> bool {
         /*
             from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-naming-conventions
 
@@ -28,7 +36,7 @@ impl ChunkType {
         Self::test_fifth_bit_to_0(self.bytes[0])
     }
 
-    fn is_public(&self) -> bool {
+    pub fn is_public(&self) -> bool {
         /*
             from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-naming-conventions
 
@@ -37,7 +45,7 @@ impl ChunkType {
         Self::test_fifth_bit_to_0(self.bytes[1])
     }
 
-    fn is_reserved_bit_valid(&self) -> bool {
+    pub fn is_reserved_bit_valid(&self) -> bool {
         /*
             from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-naming-conventions
 
@@ -46,7 +54,7 @@ impl ChunkType {
         Self::test_fifth_bit_to_0(self.bytes[2])
     }
 
-    fn is_safe_to_copy(&self) -> bool {
+    pub fn is_safe_to_copy(&self) -> bool {
         /*
             from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-naming-conventions
 
@@ -55,13 +63,40 @@ impl ChunkType {
         !Self::test_fifth_bit_to_0(self.bytes[3])
     }
 
-    fn is_valid(&self) -> bool {
+    pub fn is_valid(&self) -> bool {
         /*
             from http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html#Chunk-layout
 
             the chunk is valid if all of its bytes are ASCII uppercase or lowercase letters, and also if the reserved bit is valid
         */
-        self.bytes.iter().all(|b| b.is_ascii_alphabetic()) && self.is_reserved_bit_valid()
+        self.validation_errors().is_empty()
+    }
+
+    pub fn validate(&self, options: &ValidationOptions) -> Result<(), ChunkTypeError> {
+        // the letters are already checked when the chunk type is created
+        if !options.allow_reserved_bit_set && !self.is_reserved_bit_valid() {
+            return Err(ChunkTypeError::ReservedBitSet(self.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn validation_errors(&self) -> Vec<&'static str> {
+        self.validation_errors_with(&ValidationOptions::default())
+    }
+
+    pub fn validation_errors_with(&self, options: &ValidationOptions) -> Vec<&'static str> {
+        let mut errors = vec![];
+
+        if !self.bytes.iter().all(|b| b.is_ascii_alphabetic()) {
+            errors.push("the chunk type must only contain ASCII letters");
+        }
+
+        if !options.allow_reserved_bit_set && !self.is_reserved_bit_valid() {
+            errors.push("the third letter of the chunk type must be uppercase (reserved bit)");
+        }
+
+        errors
     }
 
     fn test_fifth_bit_to_0(byte: u8) -> bool {
@@ -125,6 +160,15 @@ mod tests {
         assert!(ChunkType::try_from([0, 0, 0, 0]).is_err());
     }
 
+    #[test]
+    pub fn test_chunk_type_from_bytes_rejects_non_letters() {
+        assert!(ChunkType::try_from([82, 117, 83, 116]).is_ok());
+        assert!(matches!(
+            ChunkType::try_from([32, 117, 83, 116]),
+            Err(ChunkTypeError::NonAlphabeticCharacters(_))
+        ));
+    }
+
     #[test]
     pub fn test_chunk_type_from_str() {
         let expected = ChunkType::try_from([82, 117, 83, 116]).unwrap();
@@ -214,6 +258,32 @@ mod tests {
         assert_eq!(&chunk.to_string(), "RuSt");
     }
 
+    #[test]
+    pub fn test_chunk_type_validation_errors() {
+        let chunk = ChunkType::from_str("Rust").unwrap();
+
+        assert_eq!(
+            chunk.validation_errors(),
+            vec!["the third letter of the chunk type must be uppercase (reserved bit)"]
+        );
+        assert!(ChunkType::from_str("RuSt")
+            .unwrap()
+            .validation_errors()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn test_chunk_type_allow_reserved_bit_set() {
+        let chunk = ChunkType::from_str("Rust").unwrap();
+        let options = ValidationOptions {
+            allow_reserved_bit_set: true,
+        };
+
+        assert!(chunk.validate(&ValidationOptions::default()).is_err());
+        assert!(chunk.validate(&options).is_ok());
+        assert!(chunk.validation_errors_with(&options).is_empty());
+    }
+
     #[test]
     pub fn test_chunk_type_trait_impls() {
         let chunk_type_1: ChunkType = TryFrom::try_from([82, 117, 83, 116]).unwrap();
@@ -230,6 +300,17 @@ mod tests {
         assert!(ChunkType::from_str("RuS").is_err());
     }
 
+    #[test]
+    pub fn test_chunk_type_too_short_reports_its_length() {
+        // the length is checked before the characters, so no NUL padding can ever sneak in
+        for s in ["", "R", "Ru", "RuS"] {
+            assert!(matches!(
+                ChunkType::from_str(s),
+                Err(ChunkTypeError::InvalidStringLength(n)) if n == s.len()
+            ));
+        }
+    }
+
     #[test]
     pub fn test_chunk_type_too_long_is_rejected() {
         let result = ChunkType::from_str("abcdefg");
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClipboardError {
+    #[error("No clipboard is available: {0}")]
+    Unavailable(String),
+    #[error("The clipboard is empty or doesn't hold any text")]
+    NoText,
+    #[error("pngme was built without clipboard support, enable the \"clipboard\" feature")]
+    NotSupported,
+}
+
+pub trait Clipboard {
+    fn get_text(&mut self) -> Result<String>;
+
+    fn set_text(&mut self, text: &str) -> Result<()>;
+}
+
+/// The clipboard of the desktop session, only available with the "clipboard" feature
+pub struct SystemClipboard {
+    #[cfg(feature = "clipboard")]
+    inner: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    #[cfg(feature = "clipboard")]
+    pub fn new() -> Result<Self> {
+        // on a headless machine there's no display server to get the clipboard from
+        let inner =
+            arboard::Clipboard::new().map_err(|e| ClipboardError::Unavailable(e.to_string()))?;
+
+        Ok(Self { inner })
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub fn new() -> Result<Self> {
+        Err(ClipboardError::NotSupported.into())
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Result<String> {
+        self.inner.get_text().map_err(|e| match e {
+            arboard::Error::ContentNotAvailable => ClipboardError::NoText.into(),
+            e => ClipboardError::Unavailable(e.to_string()).into(),
+        })
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        self.inner
+            .set_text(text)
+            .map_err(|e| ClipboardError::Unavailable(e.to_string()).into())
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Result<String> {
+        Err(ClipboardError::NotSupported.into())
+    }
+
+    fn set_text(&mut self, _text: &str) -> Result<()> {
+        Err(ClipboardError::NotSupported.into())
+    }
+}
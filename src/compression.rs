@@ -0,0 +1,210 @@
+use anyhow::Result;
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+use std::{
+    fs,
+    io::{self, Read, Write},
+};
+use thiserror::Error;
+
+// the same balance between speed and size that zlib and gzip use by default
+pub const DEFAULT_GZIP_LEVEL: u32 = 6;
+
+// chunks come from untrusted files, so a few bytes of deflate bomb can't take up all the memory
+pub const MAX_INFLATED_LENGTH: u64 = 16 * 1024 * 1024;
+
+// enough to tell a zlib stream apart from random bytes without inflating it as a whole
+const DETECTION_LENGTH: u64 = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("The gzip compression level must be between 0 and 9, found {0}")]
+    InvalidGzipLevel(u32),
+    #[error("The compressed data inflates to more than the limit of {0} bytes")]
+    InflatedTooLarge(u64),
+}
+
+pub fn deflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+
+    encoder.write_all(data)?;
+
+    Ok(encoder.finish()?)
+}
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut inflated = vec![];
+
+    // one byte past the limit is read to tell a stream of exactly the limit from a longer one
+    ZlibDecoder::new(data)
+        .take(MAX_INFLATED_LENGTH + 1)
+        .read_to_end(&mut inflated)?;
+
+    if inflated.len() as u64 > MAX_INFLATED_LENGTH {
+        return Err(CompressionError::InflatedTooLarge(MAX_INFLATED_LENGTH).into());
+    }
+
+    Ok(inflated)
+}
+
+pub fn gzip(data: &[u8], level: u32) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(vec![], Compression::new(level));
+
+    encoder.write_all(data)?;
+
+    Ok(encoder.finish()?)
+}
+
+pub fn gunzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = vec![];
+
+    GzDecoder::new(data).read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
+
+pub fn read_file(path: &str) -> Result<Vec<u8>> {
+    // files ending in .gz are transparently decompressed, like a .png.gz
+    let contents = fs::read(path)?;
+
+    if path.ends_with(".gz") {
+        gunzip(&contents)
+    } else {
+        Ok(contents)
+    }
+}
+
+pub fn write_file(path: &str, contents: &[u8], gzip_level: u32) -> Result<()> {
+    if path.ends_with(".gz") {
+        fs::write(path, gzip(contents, gzip_level)?)?;
+    } else {
+        fs::write(path, contents)?;
+    }
+
+    Ok(())
+}
+
+pub fn is_zlib(data: &[u8]) -> bool {
+    /*
+        from https://www.rfc-editor.org/rfc/rfc1950#section-2.2
+
+        a zlib stream starts with the CMF and FLG bytes, where the low 4 bits of CMF are the method
+        (8 for deflate, so with the usual 32K window CMF is 0x78) and CMF * 256 + FLG is a multiple
+        of 31, and ends with an adler32 checksum
+
+        only the start of the stream is inflated and thrown away, so the checksum is verified
+        just for streams which end within it
+    */
+    match data {
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8
+                && (*cmf as u16 * 256 + *flg as u16).is_multiple_of(31)
+                && io::copy(
+                    &mut ZlibDecoder::new(data).take(DETECTION_LENGTH),
+                    &mut io::sink(),
+                )
+                .is_ok()
+        }
+        _ => false,
+    }
+}
+
+pub fn zlib_offset(data: &[u8]) -> Option<usize> {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.zTXt
+
+        chunks like zTXt and iCCP start with a null-terminated keyword and a compression method
+        byte (0 for zlib), so the stream is also looked for right after them
+    */
+    if is_zlib(data) {
+        return Some(0);
+    }
+
+    let keyword_end = data.iter().position(|&b| b == 0)?;
+    let offset = keyword_end + 2;
+
+    (data.get(keyword_end + 1) == Some(&0) && is_zlib(data.get(offset..)?)).then_some(offset)
+}
+
+pub fn declared_method(chunk_type: &str, data: &[u8]) -> Option<u8> {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.iCCP
+
+        zTXt and iCCP store the compression method right after the null-terminated keyword, while
+        iTXt stores it after the compression flag; 0 (zlib) is the only method defined so far
+    */
+    let keyword_end = data.iter().position(|&b| b == 0)?;
+
+    match chunk_type {
+        "zTXt" | "iCCP" => data.get(keyword_end + 1).copied(),
+        "iTXt" => data.get(keyword_end + 2).copied(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_zlib() {
+        let compressed = deflate(b"I am a compressed message").unwrap();
+
+        assert!(is_zlib(&compressed));
+        assert!(!is_zlib(b"I am a plain message"));
+        assert!(!is_zlib(&compressed[..compressed.len() - 1]));
+        assert_eq!(inflate(&compressed).unwrap(), b"I am a compressed message");
+    }
+
+    #[test]
+    fn test_inflate_limit() {
+        let limit = MAX_INFLATED_LENGTH as usize;
+        let bomb = deflate(&vec![0; limit + 1]).unwrap();
+
+        assert_eq!(
+            inflate(&deflate(&vec![0; limit]).unwrap()).unwrap().len(),
+            limit
+        );
+        assert!(matches!(
+            inflate(&bomb).err().unwrap().downcast_ref(),
+            Some(CompressionError::InflatedTooLarge(MAX_INFLATED_LENGTH))
+        ));
+        // detection stops long before the end of the stream
+        assert!(is_zlib(&bomb));
+    }
+
+    #[test]
+    fn test_zlib_offset() {
+        let mut data = b"Comment\0\0".to_vec();
+
+        data.extend(deflate(b"I am a compressed comment").unwrap());
+
+        assert_eq!(zlib_offset(&deflate(b"Raw stream").unwrap()), Some(0));
+        assert_eq!(zlib_offset(&data), Some(9));
+        assert_eq!(zlib_offset(b"Comment\0I am a plain comment"), None);
+        assert_eq!(zlib_offset(b""), None);
+    }
+
+    #[test]
+    fn test_declared_method() {
+        assert_eq!(declared_method("zTXt", b"Comment\0\0data"), Some(0));
+        assert_eq!(declared_method("iCCP", b"Profile\0\x05data"), Some(5));
+        assert_eq!(declared_method("iTXt", b"Comment\0\x01\0\0\0data"), Some(0));
+        assert_eq!(declared_method("tEXt", b"Comment\0\0"), None);
+        assert_eq!(declared_method("zTXt", b"Comment"), None);
+    }
+
+    #[test]
+    fn test_gzip_levels() {
+        let data = b"I am a message that repeats. ".repeat(100);
+        let fastest = gzip(&data, 0).unwrap();
+        let smallest = gzip(&data, 9).unwrap();
+
+        assert!(smallest.len() <= fastest.len());
+        assert_eq!(gunzip(&fastest).unwrap(), data);
+        assert_eq!(gunzip(&smallest).unwrap(), data);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{chunk::Chunk, png::Png};
+use std::fs;
+
+// how many bytes are shown around the offending offset
+const CONTEXT_LENGTH: usize = 16;
+
+pub fn diagnose_file(path: &str) -> String {
+    match fs::read(path) {
+        Ok(bytes) => diagnose(&bytes),
+        Err(e) => format!("Could not read {path}: {e}\n"),
+    }
+}
+
+pub fn diagnose(bytes: &[u8]) -> String {
+    /*
+        the file is walked chunk by chunk without giving up on the first problem, which is then
+        reported with the offset where it happens and the bytes found there
+    */
+    if bytes.get(..8) != Some(&Png::STANDARD_HEADER[..]) {
+        return format!("Invalid header at offset 0\n{}", context(bytes, 0));
+    }
+
+    let mut cursor = 8;
+
+    while cursor < bytes.len() {
+        // a truncated chunk is cut at the end of the file, so that reading it reports the problem
+        let declared_length = bytes[cursor..]
+            .get(..4)
+            .map_or(0, |b| u32::from_be_bytes(b.try_into().unwrap()) as usize);
+        let end = bytes
+            .len()
+            .min(cursor.saturating_add(12).saturating_add(declared_length));
+        let chunk = match Chunk::try_from_unchecked(&bytes[cursor..end]) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                return format!(
+                    "Malformed chunk at offset {cursor}: {e}\n{}",
+                    context(bytes, cursor)
+                )
+            }
+        };
+
+        if !chunk.has_valid_crc() {
+            return format!(
+                "Chunk at offset {cursor} ({}) has stored CRC {:#010x} but computed CRC {:#010x}\n{}",
+                chunk.chunk_type(),
+                chunk.crc(),
+                chunk.calculated_crc(),
+                context(bytes, cursor)
+            );
+        }
+
+        cursor += 12 + chunk.length() as usize;
+    }
+
+    String::from("No problems found in the chunks of the file\n")
+}
+
+fn context(bytes: &[u8], offset: usize) -> String {
+    let end = bytes.len().min(offset + CONTEXT_LENGTH);
+    let hex = bytes[offset..end]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!("Bytes at offset {offset}: {hex}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_bad_crc() {
+        let chunks = vec![
+            Chunk::from_strings("FrSt", "I am the first chunk").unwrap(),
+            Chunk::from_strings("LASt", "I am the last chunk").unwrap(),
+        ];
+        let crc = chunks[1].crc();
+        let mut bytes = Png::from_chunks(chunks).as_bytes();
+        let last = bytes.len() - 1;
+
+        bytes[last] ^= 0xff;
+
+        let diagnostics = diagnose(&bytes);
+
+        assert!(diagnostics.starts_with(&format!(
+            "Chunk at offset 40 (LASt) has stored CRC {:#010x} but computed CRC {crc:#010x}\n",
+            crc ^ 0xff
+        )));
+        assert!(diagnostics
+            .ends_with("Bytes at offset 40: 00 00 00 13 4c 41 53 74 49 20 61 6d 20 74 68 65\n"));
+    }
+
+    #[test]
+    fn test_diagnose_truncated_chunk() {
+        let mut bytes = Png::from_chunks(vec![]).as_bytes();
+
+        bytes.extend([0, 0, 0, 100, 82, 117, 83, 116]);
+
+        assert!(diagnose(&bytes).starts_with("Malformed chunk at offset 8:"));
+    }
+
+    #[test]
+    fn test_diagnose_valid_file() {
+        let bytes = Png::from_chunks(vec![]).as_bytes();
+
+        assert_eq!(
+            diagnose(&bytes),
+            "No problems found in the chunks of the file\n"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{chunk::Chunk, png::Png};
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("No backup found at {0}")]
+    MissingBackup(String),
+}
+
+#[derive(PartialEq)]
+pub enum Change<'a> {
+    /// A chunk of the old file, at the given index, which is not in the new one
+    Removed(usize, &'a Chunk),
+    /// A chunk of the new file, at the given index, which was not in the old one
+    Added(usize, &'a Chunk),
+}
+
+pub fn backup_path(file_path: &str) -> String {
+    format!("{file_path}.bak")
+}
+
+pub fn diff<'a>(old: &'a Png, new: &'a Png) -> Vec<Change<'a>> {
+    /*
+        the chunks both files have in common are found with a longest common subsequence, so that
+        a chunk inserted in the middle doesn't make every chunk after it look changed
+    */
+    let (old, new) = (old.chunks(), new.chunks());
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(Change::Removed(i, &old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(j, &new[j]));
+            j += 1;
+        }
+    }
+
+    changes
+}
+
+impl Display for Change<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sign, index, chunk) = match self {
+            Change::Removed(index, chunk) => ('-', index, chunk),
+            Change::Added(index, chunk) => ('+', index, chunk),
+        };
+
+        write!(
+            f,
+            "{sign} Chunk {index}: {} ({} bytes)",
+            chunk.chunk_type(),
+            chunk.length()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_identical() {
+        assert!(diff(&testing_png(), &testing_png()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_inserted_and_removed() {
+        let old = testing_png();
+        let mut new = testing_png();
+
+        new.remove_chunk("FrSt").unwrap();
+        new.insert_chunk(1, Chunk::from_strings("TeSt", "I am new").unwrap());
+
+        let changes = diff(&old, &new)
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            changes,
+            ["- Chunk 0: FrSt (5 bytes)", "+ Chunk 1: TeSt (8 bytes)"]
+        );
+    }
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::from_strings("FrSt", "First").unwrap(),
+            Chunk::from_strings("miDl", "Middle").unwrap(),
+            Chunk::from_strings("LASt", "Last").unwrap(),
+        ])
+    }
+}
@@ -0,0 +1,89 @@
+use crate::png::Png;
+use anyhow::Result;
+use serde::Serialize;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Unsupported output format {0:?}, expected \"text\" or \"json\"")]
+    UnsupportedFormat(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChunkSummary {
+    pub length: u32,
+    #[serde(rename = "type")]
+    pub chunk_type: String,
+    pub data_len: usize,
+    pub crc: u32,
+}
+
+pub fn chunks_json(png: &Png) -> Result<String> {
+    let summaries = png
+        .chunks()
+        .iter()
+        .map(|c| ChunkSummary {
+            length: c.length(),
+            chunk_type: c.chunk_type().to_string(),
+            data_len: c.data().len(),
+            crc: c.crc(),
+        })
+        .collect::<Vec<ChunkSummary>>();
+
+    Ok(serde_json::to_string(&summaries)?)
+}
+
+impl FromStr for OutputFormat {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(FormatError::UnsupportedFormat(s.to_string())),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::Chunk;
+
+    #[test]
+    fn test_chunks_json() {
+        let png = Png::from_chunks(vec![Chunk::from_strings("RuSt", "Message").unwrap()]);
+        let crc = png.chunks()[0].crc();
+
+        assert_eq!(
+            chunks_json(&png).unwrap(),
+            format!(r#"[{{"length":7,"type":"RuSt","data_len":7,"crc":{crc}}}]"#)
+        );
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        for format in [OutputFormat::Text, OutputFormat::Json] {
+            assert_eq!(OutputFormat::from_str(&format.to_string()).unwrap(), format);
+        }
+
+        assert!(OutputFormat::from_str("xml").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{chunk::Chunk, chunk_type::ChunkType, compression};
+use anyhow::Result;
+use std::str::FromStr;
+use thiserror::Error;
+
+pub const ICCP_CHUNK_TYPE: &str = "iCCP";
+
+const MAX_NAME_LENGTH: usize = 79;
+
+#[derive(Debug, Error)]
+pub enum IccError {
+    #[error("The iCCP chunk is missing the null separator after the profile name")]
+    MissingSeparator,
+    #[error("The iCCP chunk is missing its compression method")]
+    MissingCompressionMethod,
+    #[error("Unsupported compression method {0} in the iCCP chunk, expected 0 (zlib)")]
+    UnsupportedCompressionMethod(u8),
+    #[error("The profile name must be 1 to {MAX_NAME_LENGTH} characters long, found {0}")]
+    InvalidNameLength(usize),
+    #[error("The profile name can only contain non-null Latin-1 characters, found {0:?}")]
+    InvalidNameCharacter(char),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IccProfile {
+    pub name: String,
+    pub profile: Vec<u8>,
+}
+
+impl IccProfile {
+    pub fn from_chunk(chunk: &Chunk) -> Result<Self> {
+        /*
+            from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.iCCP
+
+            the chunk holds the Latin-1 name of the profile and a null separator, then the
+            compression method (0 for zlib) and the compressed profile up to the end of the chunk
+        */
+        let data = chunk.data();
+        let separator = data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(IccError::MissingSeparator)?;
+        let (&method, compressed) = data[separator + 1..]
+            .split_first()
+            .ok_or(IccError::MissingCompressionMethod)?;
+
+        if method != 0 {
+            return Err(IccError::UnsupportedCompressionMethod(method).into());
+        }
+
+        Ok(Self {
+            name: data[..separator].iter().map(|&b| b as char).collect(),
+            profile: compression::inflate(compressed)?,
+        })
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        let length = self.name.chars().count();
+
+        if !(1..=MAX_NAME_LENGTH).contains(&length) {
+            return Err(IccError::InvalidNameLength(length).into());
+        }
+
+        // every Latin-1 character is encoded as the single byte of its code point
+        let name = self
+            .name
+            .chars()
+            .map(|c| match u8::try_from(c) {
+                Ok(b) if b != 0 => Ok(b),
+                _ => Err(IccError::InvalidNameCharacter(c)),
+            })
+            .collect::<Result<Vec<u8>, IccError>>()?;
+        let data = [name, vec![0, 0], compression::deflate(&self.profile)?].concat();
+
+        Ok(Chunk::new(ChunkType::from_str(ICCP_CHUNK_TYPE)?, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_from_chunk() {
+        let profile = b"I am not a real ICC profile".to_vec();
+        let data = [
+            &b"Custom profile\0\0"[..],
+            &compression::deflate(&profile).unwrap(),
+        ]
+        .concat();
+        let chunk = Chunk::new(ChunkType::from_str(ICCP_CHUNK_TYPE).unwrap(), data);
+
+        assert_eq!(
+            IccProfile::from_chunk(&chunk).unwrap(),
+            IccProfile {
+                name: String::from("Custom profile"),
+                profile
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile_to_chunk() {
+        let profile = IccProfile {
+            name: String::from("Caf\u{e9}"),
+            profile: b"I am not a real ICC profile".to_vec(),
+        };
+        let chunk = profile.to_chunk().unwrap();
+
+        assert_eq!(chunk.chunk_type().to_string(), ICCP_CHUNK_TYPE);
+        assert!(chunk.data().starts_with(b"Caf\xe9\0\0"));
+        assert_eq!(IccProfile::from_chunk(&chunk).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_profile_to_chunk_invalid_name() {
+        let chunk = |name: &str| {
+            IccProfile {
+                name: name.to_string(),
+                profile: vec![],
+            }
+            .to_chunk()
+        };
+
+        assert!(matches!(
+            chunk("").err().unwrap().downcast_ref(),
+            Some(IccError::InvalidNameLength(0))
+        ));
+        assert!(matches!(
+            chunk(&"a".repeat(80)).err().unwrap().downcast_ref(),
+            Some(IccError::InvalidNameLength(80))
+        ));
+        assert!(chunk(&"a".repeat(79)).is_ok());
+        assert!(matches!(
+            chunk("sRGB \u{2713}").err().unwrap().downcast_ref(),
+            Some(IccError::InvalidNameCharacter('\u{2713}'))
+        ));
+    }
+
+    #[test]
+    fn test_profile_from_malformed_chunk() {
+        let chunk =
+            |data: &[u8]| Chunk::new(ChunkType::from_str(ICCP_CHUNK_TYPE).unwrap(), data.to_vec());
+
+        assert!(matches!(
+            IccProfile::from_chunk(&chunk(b"No separator"))
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(IccError::MissingSeparator)
+        ));
+        assert!(matches!(
+            IccProfile::from_chunk(&chunk(b"Name\0"))
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(IccError::MissingCompressionMethod)
+        ));
+        assert!(matches!(
+            IccProfile::from_chunk(&chunk(b"Name\0\x01"))
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(IccError::UnsupportedCompressionMethod(1))
+        ));
+    }
+}
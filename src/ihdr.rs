@@ -0,0 +1,214 @@
+use crate::{chunk::Chunk, png::Png};
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorType {
+    Grayscale,
+    Truecolor,
+    Indexed,
+    GrayscaleAlpha,
+    TruecolorAlpha,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ihdr {
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u8,
+    pub color_type: ColorType,
+    pub compression_method: u8,
+    pub filter_method: u8,
+    pub interlace_method: u8,
+}
+
+#[derive(Debug, Error)]
+pub enum IhdrError {
+    #[error("The PNG file does not contain an IHDR chunk")]
+    MissingIhdr,
+    #[error("The IHDR chunk must contain 13 bytes of data, found {0}")]
+    InvalidLength(usize),
+    #[error("Invalid color type in the IHDR chunk: {0}")]
+    InvalidColorType(u8),
+}
+
+impl ColorType {
+    pub fn requires_palette(&self) -> bool {
+        *self == Self::Indexed
+    }
+
+    pub fn channels(&self) -> u8 {
+        // an indexed pixel is a single index into the palette
+        match self {
+            Self::Grayscale | Self::Indexed => 1,
+            Self::GrayscaleAlpha => 2,
+            Self::Truecolor => 3,
+            Self::TruecolorAlpha => 4,
+        }
+    }
+}
+
+impl TryFrom<u8> for ColorType {
+    type Error = IhdrError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        /*
+            from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.IHDR
+
+            each color type is a sum of the flags 1 (palette used), 2 (color used) and 4 (alpha used)
+        */
+        match value {
+            0 => Ok(Self::Grayscale),
+            2 => Ok(Self::Truecolor),
+            3 => Ok(Self::Indexed),
+            4 => Ok(Self::GrayscaleAlpha),
+            6 => Ok(Self::TruecolorAlpha),
+            _ => Err(IhdrError::InvalidColorType(value)),
+        }
+    }
+}
+
+impl Display for ColorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Grayscale => write!(f, "Grayscale"),
+            Self::Truecolor => write!(f, "RGB"),
+            Self::Indexed => write!(f, "Indexed"),
+            Self::GrayscaleAlpha => write!(f, "Grayscale + alpha"),
+            Self::TruecolorAlpha => write!(f, "RGBA"),
+        }
+    }
+}
+
+impl Ihdr {
+    pub fn is_interlaced(&self) -> bool {
+        // 0 means no interlacing, 1 means Adam7 interlacing
+        self.interlace_method == 1
+    }
+
+    pub fn from_png(png: &Png) -> Result<Self, IhdrError> {
+        match png.chunk_by_type("IHDR") {
+            Some(chunk) => Self::try_from(chunk),
+            None => Err(IhdrError::MissingIhdr),
+        }
+    }
+}
+
+impl Display for Ihdr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}x{}, {}, {}-bit, interlaced: {}",
+            self.width,
+            self.height,
+            self.color_type,
+            self.bit_depth,
+            if self.is_interlaced() { "yes" } else { "no" }
+        )
+    }
+}
+
+impl TryFrom<&Chunk> for Ihdr {
+    type Error = IhdrError;
+
+    fn try_from(value: &Chunk) -> Result<Self, Self::Error> {
+        /*
+            the data of an IHDR chunk is structured as follows:
+            - 4 bytes: width
+            - 4 bytes: height
+            - 1 byte each: bit depth, color type, compression method, filter method, interlace method
+        */
+        let data: &[u8; 13] = value
+            .data()
+            .try_into()
+            .map_err(|_| IhdrError::InvalidLength(value.data().len()))?;
+
+        Ok(Self {
+            width: u32::from_be_bytes(data[0..4].try_into().unwrap()),
+            height: u32::from_be_bytes(data[4..8].try_into().unwrap()),
+            bit_depth: data[8],
+            color_type: ColorType::try_from(data[9])?,
+            compression_method: data[10],
+            filter_method: data[11],
+            interlace_method: data[12],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_type::ChunkType;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_ihdr_from_chunk() {
+        let ihdr = Ihdr::try_from(&testing_ihdr_chunk(6)).unwrap();
+
+        assert_eq!(ihdr.width, 50);
+        assert_eq!(ihdr.height, 40);
+        assert_eq!(ihdr.bit_depth, 8);
+        assert_eq!(ihdr.color_type, ColorType::TruecolorAlpha);
+        assert_eq!(ihdr.interlace_method, 0);
+    }
+
+    #[test]
+    fn test_ihdr_interlaced() {
+        let mut data = testing_ihdr_chunk(2).data().to_vec();
+
+        data[12] = 1;
+
+        let ihdr = Ihdr::try_from(&Chunk::new(ChunkType::from_str("IHDR").unwrap(), data)).unwrap();
+
+        assert!(ihdr.is_interlaced());
+        assert_eq!(ihdr.to_string(), "50x40, RGB, 8-bit, interlaced: yes");
+    }
+
+    #[test]
+    fn test_ihdr_not_interlaced() {
+        let ihdr = Ihdr::try_from(&testing_ihdr_chunk(6)).unwrap();
+
+        assert!(!ihdr.is_interlaced());
+        assert_eq!(ihdr.to_string(), "50x40, RGBA, 8-bit, interlaced: no");
+    }
+
+    #[test]
+    fn test_ihdr_invalid_color_type() {
+        assert!(Ihdr::try_from(&testing_ihdr_chunk(5)).is_err());
+    }
+
+    #[test]
+    fn test_ihdr_invalid_length() {
+        let chunk = Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 12]);
+
+        assert!(Ihdr::try_from(&chunk).is_err());
+    }
+
+    #[test]
+    fn test_ihdr_missing_from_png() {
+        let png = Png::from_chunks(vec![]);
+
+        assert!(Ihdr::from_png(&png).is_err());
+    }
+
+    #[test]
+    fn test_color_type_channels() {
+        assert_eq!(ColorType::Grayscale.channels(), 1);
+        assert_eq!(ColorType::Indexed.channels(), 1);
+        assert_eq!(ColorType::GrayscaleAlpha.channels(), 2);
+        assert_eq!(ColorType::Truecolor.channels(), 3);
+        assert_eq!(ColorType::TruecolorAlpha.channels(), 4);
+    }
+
+    #[test]
+    fn test_color_type_requires_palette() {
+        assert!(ColorType::Indexed.requires_palette());
+        assert!(!ColorType::Truecolor.requires_palette());
+    }
+
+    fn testing_ihdr_chunk(color_type: u8) -> Chunk {
+        let data = [0, 0, 0, 50, 0, 0, 0, 40, 8, color_type, 0, 0, 0].to_vec();
+
+        Chunk::new(ChunkType::from_str("IHDR").unwrap(), data)
+    }
+}
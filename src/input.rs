@@ -0,0 +1,63 @@
+use crate::png::{Png, PngError};
+use anyhow::Result;
+use std::{
+    fs::{self, File},
+    io::{self, ErrorKind},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("file not found: {0}")]
+    FileNotFound(String),
+    #[error("not a valid PNG: {0}")]
+    InvalidPng(#[source] PngError),
+}
+
+fn not_found(path: &str) -> impl FnOnce(io::Error) -> anyhow::Error + '_ {
+    // the other io errors, like a missing permission, are reported as they are
+    move |e| match e.kind() {
+        ErrorKind::NotFound => InputError::FileNotFound(path.to_string()).into(),
+        _ => e.into(),
+    }
+}
+
+pub fn open(path: &str) -> Result<File> {
+    File::open(path).map_err(not_found(path))
+}
+
+pub fn read(path: &str) -> Result<Vec<u8>> {
+    fs::read(path).map_err(not_found(path))
+}
+
+pub fn parse_png(bytes: &[u8]) -> Result<Png> {
+    Png::try_from(bytes).map_err(|e| InputError::InvalidPng(e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_missing_file() {
+        let error = read("missing.png").err().unwrap();
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(InputError::FileNotFound(path)) if path == "missing.png"
+        ));
+        assert_eq!(error.to_string(), "file not found: missing.png");
+        assert!(open("missing.png").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_png() {
+        let error = parse_png(b"not a png").err().unwrap();
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(InputError::InvalidPng(PngError::InvalidHeaderError))
+        ));
+        assert!(error.to_string().starts_with("not a valid PNG: "));
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use std::{fs, str::FromStr};
+
+pub use chunk::Chunk;
+pub use chunk_type::ChunkType;
+pub use png::{Png, PngError};
+
+pub mod args;
+pub mod chunk;
+pub mod chunk_type;
+pub mod clipboard;
+pub mod compression;
+pub mod diagnostics;
+pub mod diff;
+pub mod format;
+pub mod icc;
+pub mod ihdr;
+pub mod input;
+pub mod manifest;
+pub mod order;
+pub mod padding;
+pub mod patch;
+pub mod png;
+pub mod seal;
+pub mod sidecar;
+pub mod size;
+pub mod spec;
+pub mod stream;
+#[cfg(test)]
+mod test_utils;
+pub mod text;
+#[cfg(test)]
+mod thread_safety;
+pub mod verify;
+
+/// Reads the PNG file at the given path and decodes the data of every chunk of the given type, in order.
+pub fn decode_all(path: &str, chunk_type: &str) -> Result<Vec<String>> {
+    ChunkType::from_str(chunk_type)?;
+
+    let buffer = fs::read(path)?;
+    let png = Png::try_from(&buffer[..])?;
+
+    png.chunks_by_type(chunk_type)
+        .iter()
+        .map(|c| c.data_as_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk::Chunk, test_utils::test_path};
+
+    #[test]
+    fn test_decode_all() {
+        let file_name = &test_path("test.png");
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("RuSt", "I am the first message").unwrap(),
+            Chunk::from_strings("FrSt", "I am not a message").unwrap(),
+            Chunk::from_strings("RuSt", "I am the second message").unwrap(),
+        ]);
+
+        fs::write(file_name, png.as_bytes()).unwrap();
+
+        assert_eq!(
+            decode_all(file_name, "RuSt").unwrap(),
+            vec!["I am the first message", "I am the second message"]
+        );
+        assert!(decode_all(file_name, "LASt").unwrap().is_empty());
+        assert!(decode_all(file_name, "ab").is_err());
+        assert!(decode_all(file_name, "1234").is_err());
+        fs::remove_file(file_name).unwrap();
+    }
+}
@@ -1,29 +1,280 @@
 use anyhow::Result;
-use args::{CommandType, PngMeArgs};
 use clap::Parser;
-
-mod args;
-mod chunk;
-mod chunk_type;
-mod png;
+use pngme::{
+    args::{CommandType, EncodeOutcome, PngMeArgs},
+    clipboard::SystemClipboard,
+    diagnostics,
+    format::OutputFormat,
+};
 
 fn main() -> Result<()> {
-    match PngMeArgs::parse().command_type {
+    let args = PngMeArgs::parse();
+    let debug_path = args.debug_path();
+    let report = |e| {
+        eprintln!("{e}");
+
+        if let Some(path) = &debug_path {
+            eprint!("{}", diagnostics::diagnose_file(path));
+        }
+    };
+
+    match args.configured_command() {
         CommandType::Encode(encode_args) => match encode_args.encode() {
-            Ok(_) => println!("Encoding successful"),
-            Err(e) => eprintln!("{e}"),
+            Ok(EncodeOutcome::Modified(warning)) => {
+                if let Some(w) = warning {
+                    eprintln!("Warning: {w}");
+                }
+
+                println!("Encoding successful");
+            }
+            Ok(outcome) => {
+                println!("No changes made, the file already contains the chunk");
+                std::process::exit(outcome.exit_code());
+            }
+            Err(e) => report(e),
         },
+        CommandType::Decode(decode_args) if decode_args.to_clipboard => {
+            match SystemClipboard::new().and_then(|mut c| decode_args.decode_to_clipboard(&mut c)) {
+                Ok(_) => println!("Copied the decoded message to the clipboard"),
+                Err(e) => report(e),
+            }
+        }
         CommandType::Decode(decode_args) => match decode_args.decode() {
+            Ok(s) if decode_args.with_meta || decode_args.show_index => println!("{s}"),
             Ok(s) => println!("Decoded: {s}"),
-            Err(e) => eprintln!("{e}"),
+            Err(e) => report(e),
+        },
+        CommandType::Remove(remove_args) if remove_args.check => match remove_args.check() {
+            Ok(None) => println!("{} can be safely removed", remove_args.chunk_type),
+            Ok(Some(reason)) => {
+                println!(
+                    "{} can't be safely removed: {reason}",
+                    remove_args.chunk_type
+                );
+                std::process::exit(1);
+            }
+            Err(e) => report(e),
+        },
+        CommandType::Remove(remove_args) if remove_args.all => match remove_args.remove_all() {
+            Ok(n) => println!("Removed {n} chunks"),
+            Err(e) => report(e),
         },
         CommandType::Remove(remove_args) => match remove_args.remove() {
             Ok(c) => println!("Removed: {c}"),
-            Err(e) => eprintln!("{e}"),
+            Err(e) => report(e),
         },
         CommandType::Print(print_args) => match print_args.print() {
-            Ok(p) => println!("PNG: {p}"),
-            Err(e) => eprintln!("{e}"),
+            Ok(p) if print_args.format == OutputFormat::Json => println!("{p}"),
+            Ok(p) => print!("{p}"),
+            Err(e) => report(e),
+        },
+        CommandType::ReplaceFrom(replace_from_args) => match replace_from_args.replace_from() {
+            Ok(c) => println!("Replaced: {c}"),
+            Err(e) => report(e),
+        },
+        CommandType::Verify(verify_args) => match verify_args.verify() {
+            Ok(issues) if verify_args.json => match verify_args.json_report(&issues) {
+                Ok(json) => {
+                    println!("{json}");
+
+                    match verify_args.exit_code(&issues) {
+                        0 => (),
+                        code => std::process::exit(code),
+                    }
+                }
+                Err(e) => report(e),
+            },
+            Ok(issues) if issues.is_empty() => println!("Verification successful"),
+            Ok(issues) => {
+                issues.iter().for_each(|i| println!("[{}] {i}", i.severity));
+
+                match verify_args.exit_code(&issues) {
+                    0 => (),
+                    code => std::process::exit(code),
+                }
+            }
+            Err(e) => {
+                // a file that can't even be parsed fails like an error-severity issue
+                report(e);
+                std::process::exit(1);
+            }
+        },
+        CommandType::SetExif(set_exif_args) => match set_exif_args.set_exif() {
+            Ok(c) => println!("Embedded: {c}"),
+            Err(e) => report(e),
+        },
+        CommandType::GetExif(get_exif_args) => match get_exif_args.get_exif() {
+            Ok(n) => println!("Extracted {n} bytes of EXIF data"),
+            Err(e) => report(e),
+        },
+        CommandType::Rotate(rotate_args) => match rotate_args.rotate() {
+            Ok(p) => println!("Rotated PNG: {p}"),
+            Err(e) => report(e),
+        },
+        CommandType::Minimize(minimize_args) => match minimize_args.minimize() {
+            Ok(n) => println!("Minimized: removed {n} chunks"),
+            Err(e) => report(e),
+        },
+        CommandType::SizeImpact(size_impact_args) => match size_impact_args.size_impact() {
+            Ok(savings) => savings
+                .iter()
+                .for_each(|(i, t, n)| println!("Chunk {i}: removing {t} saves {n} bytes")),
+            Err(e) => report(e),
+        },
+        CommandType::Csv(csv_args) => match csv_args.csv() {
+            Ok(s) => print!("{s}"),
+            Err(e) => report(e),
+        },
+        CommandType::FindCrc(find_crc_args) => match find_crc_args.find_crc() {
+            Ok(matches) if matches.is_empty() => println!("No chunk matches the given CRC"),
+            Ok(matches) => matches.iter().for_each(|(i, t)| println!("Chunk {i}: {t}")),
+            Err(e) => report(e),
+        },
+        CommandType::Touch(touch_args) => match touch_args.touch() {
+            Ok((n, reordered)) => println!(
+                "Touched: fixed {n} CRCs, {}",
+                if reordered {
+                    "restored the canonical chunk order"
+                } else {
+                    "the chunk order was already canonical"
+                }
+            ),
+            Err(e) => report(e),
+        },
+        CommandType::Equal(equal_args) => match equal_args.equal() {
+            Ok(true) => println!("The files are equal"),
+            Ok(false) => {
+                println!("The files are different");
+                std::process::exit(1);
+            }
+            Err(e) => report(e),
+        },
+        CommandType::Duplicates(duplicates_args) => match duplicates_args.duplicates() {
+            Ok(duplicates) => duplicates
+                .iter()
+                .for_each(|(t, n)| println!("{t}: {n} times")),
+            Err(e) => report(e),
+        },
+        CommandType::Seal(seal_args) => match seal_args.seal() {
+            Ok(()) => println!("Sealing successful"),
+            Err(e) => report(e),
+        },
+        CommandType::CheckSeal(check_seal_args) => match check_seal_args.check_seal() {
+            Ok(true) => println!("The seal matches the chunks of the file"),
+            Ok(false) => {
+                println!("The seal does not match, the file has been tampered with");
+                std::process::exit(1);
+            }
+            Err(e) => report(e),
+        },
+        CommandType::StreamOrder(stream_order_args) => match stream_order_args.stream_order() {
+            Ok(order) => order.iter().for_each(|(i, t, late)| match late {
+                Some(l) => println!("Chunk {i}: {t} (too late, it must come before {l})"),
+                None => println!("Chunk {i}: {t}"),
+            }),
+            Err(e) => report(e),
+        },
+        CommandType::Text(text_args) => match text_args.text() {
+            Ok(text) => text
+                .iter()
+                .for_each(|(keyword, value)| println!("{keyword}: {value}")),
+            Err(e) => report(e),
+        },
+        CommandType::CrcDebug(crc_debug_args) => match crc_debug_args.crc_debug() {
+            Ok(s) => print!("{s}"),
+            Err(e) => report(e),
+        },
+        CommandType::Clean(clean_args) => match clean_args.clean() {
+            Ok(0) => println!("The file was already clean"),
+            Ok(n) => println!("Cleaned: discarded {n} bytes after IEND"),
+            Err(e) => report(e),
+        },
+        CommandType::List(list_args) => match list_args.list() {
+            Ok(chunk_types) => chunk_types.iter().for_each(|t| println!("{t}")),
+            Err(e) => report(e),
+        },
+        CommandType::Manifest(manifest_args) => match manifest_args.manifest() {
+            Ok(s) => println!("{s}"),
+            Err(e) => report(e),
+        },
+        CommandType::Build(build_args) => match build_args.build() {
+            Ok(n) => println!("Built a PNG file with {n} chunks"),
+            Err(e) => report(e),
+        },
+        CommandType::Color(color_args) => match color_args.color() {
+            Ok(s) => println!("Color: {s}"),
+            Err(e) => report(e),
+        },
+        CommandType::DiffBackup(diff_backup_args) => match diff_backup_args.diff_backup() {
+            Ok(changes) if changes.is_empty() => println!("The file is the same as its backup"),
+            Ok(changes) => changes.iter().for_each(|c| println!("{c}")),
+            Err(e) => report(e),
+        },
+        CommandType::Validate(validate_args) => match validate_args.validate() {
+            Ok(results) => {
+                println!("Signature: valid");
+                results.iter().enumerate().for_each(|(i, (t, valid))| {
+                    println!(
+                        "Chunk {i}: {t} {}",
+                        if *valid {
+                            "pass"
+                        } else {
+                            "fail (CRC mismatch)"
+                        }
+                    )
+                });
+            }
+            Err(e) => report(e),
+        },
+        CommandType::Reorder(reorder_args) => match reorder_args.reorder() {
+            Ok(true) => println!("Reordered the chunks"),
+            Ok(false) => println!("The chunks were already in order"),
+            Err(e) => report(e),
+        },
+        CommandType::CapacityReport(capacity_report_args) => {
+            match capacity_report_args.capacity_report() {
+                Ok(files) => {
+                    files
+                        .iter()
+                        .for_each(|(path, hidden_bytes)| match hidden_bytes {
+                            Ok(n) => println!("{path}: {n} bytes"),
+                            Err(e) => println!("{path}: skipped, {e}"),
+                        });
+                    println!(
+                        "Total: {} bytes",
+                        files.iter().flat_map(|(_, n)| n).sum::<usize>()
+                    );
+                }
+                Err(e) => report(e),
+            }
+        }
+        CommandType::Extract(extract_args) => match extract_args.extract() {
+            Ok(n) => println!("Extracted {n} bytes"),
+            Err(e) => report(e),
+        },
+        CommandType::GetIcc(get_icc_args) => match get_icc_args.get_icc() {
+            Ok(p) => println!(
+                "Extracted the ICC profile \"{}\" ({} bytes)",
+                p.name,
+                p.profile.len()
+            ),
+            Err(e) => report(e),
+        },
+        CommandType::SetIcc(set_icc_args) => match set_icc_args.set_icc() {
+            Ok(c) => println!(
+                "Embedded the ICC profile in a {} bytes iCCP chunk",
+                c.length()
+            ),
+            Err(e) => report(e),
+        },
+        CommandType::Completions(completions_args) => match completions_args.completions() {
+            Ok(s) => print!("{s}"),
+            Err(e) => report(e),
+        },
+        #[cfg(feature = "decode-check")]
+        CommandType::DecodeCheck(decode_check_args) => match decode_check_args.decode_check() {
+            Ok((w, h)) => println!("Decoded a {w}x{h} image successfully"),
+            Err(e) => report(e),
         },
     }
 
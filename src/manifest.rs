@@ -0,0 +1,87 @@
+use crate::{chunk::Chunk, chunk_type::ChunkType, png::Png};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub chunks: Vec<ManifestChunk>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestChunk {
+    pub index: usize,
+    #[serde(rename = "type")]
+    pub chunk_type: String,
+    /// The data of the chunk encoded in base64
+    pub data: String,
+}
+
+impl Manifest {
+    pub fn from_png(png: &Png) -> Self {
+        let chunks = png
+            .chunks()
+            .iter()
+            .enumerate()
+            .map(|(index, c)| ManifestChunk {
+                index,
+                chunk_type: c.chunk_type().to_string(),
+                data: STANDARD.encode(c.data()),
+            })
+            .collect();
+
+        Self { chunks }
+    }
+
+    pub fn to_png(&self) -> Result<Png> {
+        // the chunks are placed by their index, so a manifest edited by hand can list them in any order
+        let mut entries = self.chunks.iter().collect::<Vec<&ManifestChunk>>();
+
+        entries.sort_by_key(|e| e.index);
+
+        let chunks = entries
+            .into_iter()
+            .map(|e| {
+                Ok(Chunk::new(
+                    ChunkType::from_str(&e.chunk_type)?,
+                    STANDARD.decode(&e.data)?,
+                ))
+            })
+            .collect::<Result<Vec<Chunk>>>()?;
+
+        Ok(Png::from_chunks(chunks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let png = Png::from_chunks(vec![
+            Chunk::from_strings("FrSt", "I am the first chunk").unwrap(),
+            Chunk::new(ChunkType::from_str("RuSt").unwrap(), vec![0, 159, 255]),
+        ]);
+        let manifest = Manifest::from_png(&png);
+
+        assert_eq!(manifest.chunks[1].data, "AJ//");
+        assert_eq!(manifest.to_png().unwrap().as_bytes(), png.as_bytes());
+    }
+
+    #[test]
+    fn test_manifest_chunks_in_any_order() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{"chunks": [
+                {"index": 1, "type": "LASt", "data": "TGFzdA=="},
+                {"index": 0, "type": "FrSt", "data": "Rmlyc3Q="}
+            ]}"#,
+        )
+        .unwrap();
+        let png = manifest.to_png().unwrap();
+
+        assert_eq!(png.chunks()[0].data_as_string().unwrap(), "First");
+        assert_eq!(png.chunks()[1].data_as_string().unwrap(), "Last");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::png::Png;
+use anyhow::Result;
+use serde::Deserialize;
+use std::fs;
+
+pub const CONFIG_FILE_NAME: &str = ".pngme-order.toml";
+
+// the entry of the order standing for every chunk type which is not listed
+pub const UNKNOWN_BUCKET: &str = "*";
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct OrderConfig {
+    /// The chunk types in the required order, where "*" is the place of the unlisted ones
+    pub order: Vec<String>,
+}
+
+impl OrderConfig {
+    pub fn load(path: &str) -> Result<Self> {
+        /*
+            a house layout is written as
+
+                order = ["gAMA", "tEXt", "*", "IDAT"]
+
+            and the unlisted chunks go at the end, right before IEND, unless "*" is used
+        */
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn rank(&self, chunk_type: &str) -> usize {
+        // IHDR and IEND keep their places at the very start and at the very end of the file
+        let position = |t: &str| self.order.iter().position(|o| o == t);
+
+        match chunk_type {
+            "IHDR" => 0,
+            "IEND" => usize::MAX,
+            _ => {
+                position(chunk_type)
+                    .or_else(|| position(UNKNOWN_BUCKET))
+                    .unwrap_or(self.order.len())
+                    + 1
+            }
+        }
+    }
+
+    pub fn reorder(&self, png: &Png) -> Option<Png> {
+        // the sort is stable, so chunks with the same rank keep their relative order
+        let mut chunks = png.chunks().to_vec();
+
+        chunks.sort_by_key(|c| self.rank(&c.chunk_type().to_string()));
+
+        (chunks != png.chunks()).then(|| Png::from_chunks(chunks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::Chunk;
+
+    #[test]
+    fn test_reorder() {
+        let config: OrderConfig = toml::from_str(r#"order = ["tEXt", "*", "gAMA"]"#).unwrap();
+        let png = testing_png(&["IHDR", "gAMA", "RuSt", "tEXt", "IEND"]);
+        let reordered = config.reorder(&png).unwrap();
+
+        assert_eq!(types(&reordered), ["IHDR", "tEXt", "RuSt", "gAMA", "IEND"]);
+        assert!(config.reorder(&reordered).is_none());
+    }
+
+    #[test]
+    fn test_reorder_unlisted_at_end() {
+        let config: OrderConfig = toml::from_str(r#"order = ["IDAT", "tEXt"]"#).unwrap();
+        let png = testing_png(&["IHDR", "RuSt", "tEXt", "IDAT", "IEND"]);
+
+        assert_eq!(
+            types(&config.reorder(&png).unwrap()),
+            ["IHDR", "IDAT", "tEXt", "RuSt", "IEND"]
+        );
+    }
+
+    fn testing_png(chunk_types: &[&str]) -> Png {
+        Png::from_chunks(
+            chunk_types
+                .iter()
+                .map(|t| Chunk::from_strings(t, "").unwrap())
+                .collect(),
+        )
+    }
+
+    fn types(png: &Png) -> Vec<String> {
+        png.chunks()
+            .iter()
+            .map(|c| c.chunk_type().to_string())
+            .collect()
+    }
+}
@@ -0,0 +1,56 @@
+use thiserror::Error;
+
+// the big-endian u32 in front of the message, read back by Chunk::read_length_prefixed
+const PREFIX_LENGTH: usize = 4;
+
+#[derive(Debug, Error)]
+pub enum PaddingError {
+    #[error(
+        "The message takes {0} bytes with its length prefix, more than the {1} bytes to pad it to"
+    )]
+    MessageTooLong(usize, usize),
+}
+
+pub fn pad(data: &[u8], length: usize) -> Result<Vec<u8>, PaddingError> {
+    /*
+        the message is prefixed with its length and followed by zeros up to the given length, so
+        every padded chunk has the same size no matter how long the message really is
+    */
+    let framed_length = PREFIX_LENGTH + data.len();
+
+    if framed_length > length {
+        return Err(PaddingError::MessageTooLong(framed_length, length));
+    }
+
+    let prefix = u32::try_from(data.len())
+        .map_err(|_| PaddingError::MessageTooLong(framed_length, length))?
+        .to_be_bytes();
+    let mut padded = Vec::with_capacity(length);
+
+    padded.extend(prefix);
+    padded.extend(data);
+    padded.resize(length, 0);
+
+    Ok(padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad() {
+        let padded = pad(b"Hi", 10).unwrap();
+
+        assert_eq!(padded, [0, 0, 0, 2, b'H', b'i', 0, 0, 0, 0]);
+        assert_eq!(pad(b"Hi", 6).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_pad_message_too_long() {
+        assert!(matches!(
+            pad(b"Hi", 5),
+            Err(PaddingError::MessageTooLong(6, 5))
+        ));
+    }
+}
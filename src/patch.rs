@@ -0,0 +1,113 @@
+use std::io::{self, BufReader, Read};
+use thiserror::Error;
+
+pub struct Patch {
+    offset: u64,
+    bytes: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+pub enum PatchError {
+    #[error("IO Error converting from bytes: {0}")]
+    MalformedPatch(#[from] io::Error),
+    #[error("The patch declares {0} inserted bytes but contains {1}")]
+    InvalidLength(u64, usize),
+    #[error("The patch inserts bytes at offset {0}, past the end of the file")]
+    OffsetOutOfBounds(u64),
+}
+
+impl Patch {
+    pub fn new(offset: u64, bytes: Vec<u8>) -> Self {
+        Self { offset, bytes }
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn apply(&self, original: &[u8]) -> Result<Vec<u8>, PatchError> {
+        if self.offset > original.len() as u64 {
+            return Err(PatchError::OffsetOutOfBounds(self.offset));
+        }
+
+        let (before, after) = original.split_at(self.offset as usize);
+
+        Ok([before, &self.bytes, after].concat())
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.offset
+            .to_be_bytes()
+            .iter()
+            .chain((self.bytes.len() as u64).to_be_bytes().iter())
+            .chain(self.bytes.iter())
+            .copied()
+            .collect()
+    }
+}
+
+impl TryFrom<&[u8]> for Patch {
+    type Error = PatchError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        /*
+            a slice of u8 (byte) interpreted as a patch is structured as follows:
+            - first 8 bytes: offset at which the bytes are inserted
+            - next 8 bytes: number of inserted bytes (n)
+            - last n bytes: inserted bytes
+        */
+        let mut input_stream = BufReader::new(value);
+        let mut buffer_8_bytes = [0u8; 8];
+
+        input_stream.read_exact(&mut buffer_8_bytes)?;
+
+        let offset = u64::from_be_bytes(buffer_8_bytes);
+
+        input_stream.read_exact(&mut buffer_8_bytes)?;
+
+        let length = u64::from_be_bytes(buffer_8_bytes);
+        let mut bytes = vec![];
+
+        input_stream.read_to_end(&mut bytes)?;
+
+        if bytes.len() as u64 != length {
+            return Err(PatchError::InvalidLength(length, bytes.len()));
+        }
+
+        Ok(Self { offset, bytes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_roundtrip() {
+        let patch = Patch::new(8, b"inserted".to_vec());
+        let parsed = Patch::try_from(&patch.as_bytes()[..]).unwrap();
+
+        assert_eq!(parsed.offset(), 8);
+        assert_eq!(parsed.bytes(), b"inserted");
+    }
+
+    #[test]
+    fn test_patch_apply() {
+        let patch = Patch::new(5, b", world".to_vec());
+
+        assert_eq!(patch.apply(b"Hello!").unwrap(), b"Hello, world!");
+        assert!(patch.apply(b"Hey").is_err());
+    }
+
+    #[test]
+    fn test_patch_invalid_length() {
+        let bytes = Patch::new(0, b"inserted".to_vec()).as_bytes();
+
+        assert!(Patch::try_from(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Patch::try_from(&bytes[..12]).is_err());
+    }
+}
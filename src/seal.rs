@@ -0,0 +1,123 @@
+use crate::{
+    chunk::Chunk,
+    chunk_type::ChunkType,
+    png::{Png, Position},
+};
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use thiserror::Error;
+
+pub const SEAL_CHUNK_TYPE: &str = "cKsm";
+
+#[derive(Debug, Error)]
+pub enum SealError {
+    #[error("The PNG file has not been sealed")]
+    MissingSeal,
+    #[error("The SHA-256 hash of the file is {1}, but {0} was expected")]
+    HashMismatch(String, String),
+}
+
+pub fn digest(png: &Png) -> Vec<u8> {
+    // the hash covers every chunk except the seal itself, which could not contain its own hash
+    let mut hasher = Sha256::new();
+
+    for chunk in png.chunks() {
+        if chunk.chunk_type().to_string() != SEAL_CHUNK_TYPE {
+            hasher.update(chunk.as_bytes());
+        }
+    }
+
+    hasher.finalize().to_vec()
+}
+
+pub fn seal(png: &mut Png) -> Result<()> {
+    let chunk = Chunk::new(ChunkType::from_str(SEAL_CHUNK_TYPE)?, digest(png));
+
+    // sealing again replaces the old seal instead of piling a new one on top of it
+    while png.remove_chunk(SEAL_CHUNK_TYPE).is_ok() {}
+    png.insert_at_position(chunk, Position::BeforeIend)?;
+
+    Ok(())
+}
+
+pub fn check(png: &Png) -> Result<bool> {
+    match png.chunk_by_type(SEAL_CHUNK_TYPE) {
+        Some(chunk) => Ok(chunk.data() == digest(png)),
+        None => Err(SealError::MissingSeal.into()),
+    }
+}
+
+pub fn check_hash(bytes: &[u8], expected: &str) -> Result<(), SealError> {
+    // unlike the seal, this hash covers the whole file as it is stored, signature included
+    let found = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+
+    if found.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(SealError::HashMismatch(expected.to_string(), found))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_check() {
+        let mut png = testing_png();
+
+        seal(&mut png).unwrap();
+
+        assert!(check(&png).unwrap());
+        assert_eq!(png.chunks()[2].chunk_type().to_string(), SEAL_CHUNK_TYPE);
+
+        seal(&mut png).unwrap();
+
+        assert_eq!(png.chunks().len(), 4);
+        assert!(check(&png).unwrap());
+    }
+
+    #[test]
+    fn test_check_detects_tampering() {
+        let mut png = testing_png();
+
+        seal(&mut png).unwrap();
+        png.remove_chunk("RuSt").unwrap();
+        png.insert_at_position(
+            Chunk::from_strings("RuSt", "I have been tampered with").unwrap(),
+            Position::BeforeIdat,
+        )
+        .unwrap();
+
+        assert!(!check(&png).unwrap());
+    }
+
+    #[test]
+    fn test_check_without_seal() {
+        assert!(check(&testing_png()).is_err());
+    }
+
+    #[test]
+    fn test_check_hash() {
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(check_hash(b"abc", hash).is_ok());
+        assert!(check_hash(b"abc", &hash.to_uppercase()).is_ok());
+        assert!(matches!(
+            check_hash(b"abd", hash),
+            Err(SealError::HashMismatch(..))
+        ));
+    }
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::from_strings("RuSt", "I am a message").unwrap(),
+            Chunk::from_strings("IDAT", "").unwrap(),
+            Chunk::from_strings("IEND", "").unwrap(),
+        ])
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use std::{collections::HashMap, fs, io::ErrorKind};
+
+pub fn path_for(png_path: &str) -> String {
+    format!("{png_path}.meta")
+}
+
+pub fn load_descriptions(png_path: &str) -> Result<HashMap<String, String>> {
+    /*
+        the sidecar of file.png is file.png.meta, a JSON object mapping chunk types to their
+        descriptions, and it's optional so a missing one just means there are no descriptions
+    */
+    match fs::read(path_for(png_path)) {
+        Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_path;
+
+    fn png_name() -> String {
+        test_path("sidecar.png")
+    }
+
+    #[test]
+    fn test_load_descriptions() {
+        fs::write(path_for(&png_name()), r#"{"tEXt": "Textual data"}"#).unwrap();
+
+        let descriptions = load_descriptions(&png_name()).unwrap();
+
+        assert_eq!(descriptions.len(), 1);
+        assert_eq!(descriptions["tEXt"], "Textual data");
+        fs::remove_file(path_for(&png_name())).unwrap();
+    }
+
+    #[test]
+    fn test_missing_sidecar() {
+        assert!(load_descriptions(&png_name()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_sidecar() {
+        fs::write(path_for(&png_name()), "not json").unwrap();
+
+        assert!(load_descriptions(&png_name()).is_err());
+        fs::remove_file(path_for(&png_name())).unwrap();
+    }
+}
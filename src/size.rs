@@ -0,0 +1,38 @@
+pub fn human_readable(bytes: u64) -> String {
+    // binary prefixes, so that 1024 bytes are reported as 1.0 KiB
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_readable_bytes() {
+        assert_eq!(human_readable(0), "0 B");
+        assert_eq!(human_readable(1023), "1023 B");
+    }
+
+    #[test]
+    fn test_human_readable_units() {
+        assert_eq!(human_readable(1024), "1.0 KiB");
+        assert_eq!(human_readable(1536), "1.5 KiB");
+        assert_eq!(human_readable(1_048_576), "1.0 MiB");
+        assert_eq!(human_readable(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(human_readable(u64::MAX), "16777216.0 TiB");
+    }
+}
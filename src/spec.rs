@@ -0,0 +1,138 @@
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SpecVersion {
+    V1_2,
+    #[default]
+    V3,
+}
+
+#[derive(Debug, Error)]
+pub enum SpecError {
+    #[error("Unsupported PNG specification version {0:?}, expected \"1.2\" or \"3\"")]
+    UnsupportedVersion(String),
+}
+
+impl SpecVersion {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html
+        and https://www.w3.org/TR/png-3/#4Concepts.FormatTypes
+
+        the chunk types defined by each version of the specification, the third edition is a superset
+        of 1.2 which also includes the eXIf, APNG and HDR related chunks
+    */
+    const PNG_1_2_CHUNKS: [&'static str; 18] = [
+        "IHDR", "PLTE", "IDAT", "IEND", "cHRM", "gAMA", "iCCP", "sBIT", "sRGB", "bKGD", "hIST",
+        "tRNS", "pHYs", "sPLT", "tIME", "iTXt", "tEXt", "zTXt",
+    ];
+    const PNG_3_CHUNKS: [&'static str; 7] =
+        ["eXIf", "acTL", "fcTL", "fdAT", "cICP", "mDCV", "cLLI"];
+
+    pub fn is_known(&self, chunk_type: &str) -> bool {
+        let added_chunks: &[&str] = match self {
+            Self::V1_2 => &[],
+            Self::V3 => &Self::PNG_3_CHUNKS,
+        };
+
+        Self::PNG_1_2_CHUNKS
+            .iter()
+            .chain(added_chunks.iter())
+            .any(|&c| c == chunk_type)
+    }
+}
+
+pub fn canonical_rank(chunk_type: &str) -> u8 {
+    /*
+        from https://www.w3.org/TR/png-3/#5ChunkOrdering
+
+        IHDR comes first, the color space chunks and acTL before PLTE, tRNS, bKGD, hIST and the
+        chunks with no constraints between PLTE and the image data, IDAT and the frames of an
+        animation together, and IEND last
+    */
+    match chunk_type {
+        "IHDR" => 0,
+        "cHRM" | "gAMA" | "iCCP" | "sBIT" | "sRGB" | "cICP" | "mDCV" | "cLLI" | "acTL" => 1,
+        "PLTE" => 2,
+        "IDAT" | "fcTL" | "fdAT" => 4,
+        "IEND" => 5,
+        _ => 3,
+    }
+}
+
+pub fn must_precede(chunk_type: &str) -> &'static [&'static str] {
+    /*
+        from https://www.w3.org/TR/png-3/#5ChunkOrdering
+
+        the chunks that a decoder must have already seen when it reaches each of these ones,
+        otherwise it has already started decoding the palette or the image data without them
+    */
+    match chunk_type {
+        "cHRM" | "gAMA" | "iCCP" | "sBIT" | "sRGB" | "cICP" | "mDCV" | "cLLI" => &["PLTE", "IDAT"],
+        "PLTE" | "tRNS" | "bKGD" | "hIST" | "pHYs" | "sPLT" | "eXIf" | "acTL" => &["IDAT"],
+        _ => &[],
+    }
+}
+
+impl FromStr for SpecVersion {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1.2" => Ok(Self::V1_2),
+            "3" | "3.0" => Ok(Self::V3),
+            _ => Err(SpecError::UnsupportedVersion(s.to_string())),
+        }
+    }
+}
+
+impl Display for SpecVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V1_2 => write!(f, "1.2"),
+            Self::V3 => write!(f, "3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_version_from_str() {
+        assert_eq!(SpecVersion::from_str("1.2").unwrap(), SpecVersion::V1_2);
+        assert_eq!(SpecVersion::from_str("3").unwrap(), SpecVersion::V3);
+        assert!(SpecVersion::from_str("2").is_err());
+    }
+
+    #[test]
+    fn test_exif_is_known_only_in_newer_spec() {
+        assert!(!SpecVersion::V1_2.is_known("eXIf"));
+        assert!(SpecVersion::V3.is_known("eXIf"));
+    }
+
+    #[test]
+    fn test_canonical_rank() {
+        let mut types = ["IEND", "tEXt", "IDAT", "PLTE", "gAMA", "IHDR"];
+
+        types.sort_by_key(|t| canonical_rank(t));
+        assert_eq!(types, ["IHDR", "gAMA", "PLTE", "tEXt", "IDAT", "IEND"]);
+    }
+
+    #[test]
+    fn test_must_precede() {
+        assert_eq!(must_precede("gAMA"), ["PLTE", "IDAT"]);
+        assert_eq!(must_precede("tRNS"), ["IDAT"]);
+        assert!(must_precede("tEXt").is_empty());
+    }
+
+    #[test]
+    fn test_standard_chunks_are_known_in_every_spec() {
+        for spec in [SpecVersion::V1_2, SpecVersion::V3] {
+            assert!(spec.is_known("IHDR"));
+            assert!(spec.is_known("tEXt"));
+            assert!(!spec.is_known("RuSt"));
+        }
+    }
+}
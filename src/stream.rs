@@ -0,0 +1,662 @@
+use crate::{
+    chunk::Chunk,
+    chunk_type::ChunkType,
+    png::{Png, PngError},
+};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, write::EncoderWriter};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+    /// The maximum number of bytes read from the input as a whole, without any limit if None
+    pub max_total_bytes: Option<u64>,
+}
+
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("The input exceeds the limit of {0} bytes")]
+    LimitExceeded(u64),
+}
+
+struct ChunkHeader {
+    length: u32,
+    chunk_type: ChunkType,
+}
+
+impl ChunkHeader {
+    // 4 bytes of length, 4 bytes of chunk type and 4 bytes of crc
+    const OVERHEAD: u64 = 12;
+
+    fn data_and_crc_length(&self) -> u64 {
+        self.length as u64 + 4
+    }
+}
+
+struct LimitedReader<R: Read> {
+    inner: R,
+    limit: u64,
+    remaining: u64,
+}
+
+impl<R: Read> LimitedReader<R> {
+    fn new(inner: R, limit: Option<u64>) -> Self {
+        let limit = limit.unwrap_or(u64::MAX);
+
+        Self {
+            inner,
+            limit,
+            remaining: limit,
+        }
+    }
+
+    fn check(&self, length: u64) -> Result<(), StreamError> {
+        // declared lengths are checked before allocating anything for them
+        if length > self.remaining {
+            return Err(StreamError::LimitExceeded(self.limit));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            // reaching the end of the input right at the limit is still fine
+            return match self.inner.read(&mut [0u8; 1])? {
+                0 => Ok(0),
+                _ => Err(io::Error::other(StreamError::LimitExceeded(self.limit))),
+            };
+        }
+
+        let max_length = buf
+            .len()
+            .min(self.remaining.min(usize::MAX as u64) as usize);
+        let read_length = self.inner.read(&mut buf[..max_length])?;
+
+        self.remaining -= read_length as u64;
+
+        Ok(read_length)
+    }
+}
+
+fn read_signature<R: Read>(reader: &mut R) -> Result<[u8; 8]> {
+    let mut signature = [0u8; 8];
+
+    reader
+        .read_exact(&mut signature)
+        .map_err(|_| PngError::InvalidHeaderError)?;
+
+    if signature != Png::STANDARD_HEADER {
+        return Err(PngError::InvalidHeaderError.into());
+    }
+
+    Ok(signature)
+}
+
+fn read_chunk_header<R: Read>(reader: &mut R) -> Result<Option<ChunkHeader>> {
+    // reaching the end of the stream is only valid between two chunks
+    let mut buffer = [0u8; 8];
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(Some(ChunkHeader {
+        length: u32::from_be_bytes(buffer[..4].try_into().unwrap()),
+        chunk_type: ChunkType::try_from(<[u8; 4]>::try_from(&buffer[4..]).unwrap())?,
+    }))
+}
+
+fn stream_length<S: Seek>(stream: &mut S) -> Result<u64> {
+    let position = stream.stream_position()?;
+    let length = stream.seek(SeekFrom::End(0))?;
+
+    stream.seek(SeekFrom::Start(position))?;
+
+    Ok(length)
+}
+
+fn skip_chunk_data<S: Seek>(stream: &mut S, header: &ChunkHeader, length: u64) -> Result<()> {
+    // the declared length is checked against the stream, seeking past its end would go unnoticed
+    let offset = stream.stream_position()? - 8;
+    let end = offset + ChunkHeader::OVERHEAD + header.length as u64;
+
+    if end > length {
+        return Err(PngError::TruncatedChunk(
+            offset as usize,
+            (end - offset) as usize,
+            (length - offset) as usize,
+        )
+        .into());
+    }
+
+    stream.seek(SeekFrom::Start(end))?;
+
+    Ok(())
+}
+
+pub fn copy_exact<R: Read, W: Write>(reader: &mut R, writer: &mut W, length: u64) -> Result<()> {
+    if io::copy(&mut reader.take(length), writer)? != length {
+        return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(())
+}
+
+pub fn copy_exact_base64<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    length: u64,
+) -> Result<()> {
+    // the data goes through the encoder a block at a time, so it's never encoded in memory as a whole
+    let mut encoder = EncoderWriter::new(writer, &STANDARD);
+
+    copy_exact(reader, &mut encoder, length)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+pub fn read_png<R: Read>(reader: R, options: &ParseOptions) -> Result<Png> {
+    // unlike Png::try_from the input is read one chunk at a time, so it doesn't need to be in memory
+    let mut reader = LimitedReader::new(reader, options.max_total_bytes);
+    let mut chunks = vec![];
+
+    read_signature(&mut reader)?;
+
+    while let Some(header) = read_chunk_header(&mut reader)? {
+        reader.check(header.data_and_crc_length())?;
+
+        let mut chunk_bytes = vec![0u8; (ChunkHeader::OVERHEAD + header.length as u64) as usize];
+
+        chunk_bytes[..4].copy_from_slice(&header.length.to_be_bytes());
+        chunk_bytes[4..8].copy_from_slice(&header.chunk_type.bytes());
+        reader.read_exact(&mut chunk_bytes[8..])?;
+        chunks.push(Chunk::try_from(&chunk_bytes[..])?);
+    }
+
+    Ok(Png::from_chunks(chunks))
+}
+
+pub fn find_chunk_data<R: Read + Seek>(reader: &mut R, chunk_type: &str) -> Result<Option<u64>> {
+    /*
+        seeks past the data of every chunk until the first one of the given type, just like
+        Png::chunk_by_type, and leaves the reader at the start of its data, returning its length
+    */
+    let length = stream_length(reader)?;
+
+    read_signature(reader)?;
+
+    while let Some(header) = read_chunk_header(reader)? {
+        if header.chunk_type.to_string() == chunk_type {
+            return Ok(Some(header.length as u64));
+        }
+
+        skip_chunk_data(reader, &header, length)?;
+    }
+
+    Ok(None)
+}
+
+pub fn read_chunk_headers<R: Read + Seek>(reader: &mut R) -> Result<Vec<(u32, ChunkType)>> {
+    // only the length and type of each chunk are read, their data and crc are skipped unchecked
+    let mut headers = vec![];
+    let length = stream_length(reader)?;
+
+    read_signature(reader)?;
+
+    while let Some(header) = read_chunk_header(reader)? {
+        skip_chunk_data(reader, &header, length)?;
+        headers.push((header.length, header.chunk_type));
+    }
+
+    Ok(headers)
+}
+
+pub fn extract_chunk_data<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    chunk_type: &str,
+) -> Result<u64> {
+    // the data is copied without ever being held in memory as a whole, so its crc is not verified
+    let length = find_chunk_data(reader, chunk_type)?.ok_or(PngError::ChunkNotFoundError)?;
+
+    copy_exact(reader, writer, length)?;
+
+    Ok(length)
+}
+
+pub fn insert_before_end<F: Read + Write + Seek>(
+    file: &mut F,
+    chunk: &Chunk,
+) -> Result<Vec<String>> {
+    /*
+        only the chunk headers are read to find the first IEND, just like Png::insert_before_end,
+        then everything from there on is read and written again after the new chunk, so the
+        returned chunk types are the ones of the resulting file
+    */
+    let mut chunk_types = vec![];
+    let mut iend_offset = None;
+    let length = stream_length(file)?;
+
+    read_signature(file)?;
+
+    while let Some(header) = read_chunk_header(file)? {
+        if iend_offset.is_none() && header.chunk_type.to_string() == "IEND" {
+            iend_offset = Some(file.stream_position()? - 8);
+            chunk_types.push(chunk.chunk_type().to_string());
+        }
+
+        chunk_types.push(header.chunk_type.to_string());
+        skip_chunk_data(file, &header, length)?;
+    }
+
+    let tail_offset = match iend_offset {
+        Some(offset) => offset,
+        None => {
+            chunk_types.push(chunk.chunk_type().to_string());
+            file.stream_position()?
+        }
+    };
+    let mut tail = vec![];
+
+    file.seek(SeekFrom::Start(tail_offset))?;
+    file.read_to_end(&mut tail)?;
+    file.seek(SeekFrom::Start(tail_offset))?;
+    file.write_all(&chunk.as_bytes())?;
+    file.write_all(&tail)?;
+
+    Ok(chunk_types)
+}
+
+pub fn remove_chunk<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    chunk_type: &str,
+) -> Result<(Chunk, usize)> {
+    /*
+        the first pass only reads the chunk headers, seeking past their data, to find the last
+        chunk of the given type just like Png::remove_chunk does
+
+        the second pass copies every other chunk to the writer without keeping it in memory, so
+        their crc is not verified, and returns the removed chunk along with the remaining ones
+    */
+    let start = reader.stream_position()?;
+    let mut target = None;
+    let mut count = 0;
+    let length = stream_length(reader)?;
+
+    read_signature(reader)?;
+
+    while let Some(header) = read_chunk_header(reader)? {
+        if header.chunk_type.to_string() == chunk_type {
+            target = Some(count);
+        }
+
+        skip_chunk_data(reader, &header, length)?;
+        count += 1;
+    }
+
+    let target = target.ok_or(PngError::ChunkNotFoundError)?;
+    let mut removed_chunk = None;
+
+    reader.seek(SeekFrom::Start(start))?;
+    writer.write_all(&read_signature(reader)?)?;
+
+    for index in 0..count {
+        let header = read_chunk_header(reader)?.ok_or(PngError::ChunkNotFoundError)?;
+
+        if index == target {
+            let mut chunk_bytes =
+                vec![0u8; (ChunkHeader::OVERHEAD + header.length as u64) as usize];
+
+            chunk_bytes[..4].copy_from_slice(&header.length.to_be_bytes());
+            chunk_bytes[4..8].copy_from_slice(&header.chunk_type.bytes());
+            reader.read_exact(&mut chunk_bytes[8..])?;
+            removed_chunk = Some(Chunk::try_from(&chunk_bytes[..])?);
+        } else {
+            writer.write_all(&header.length.to_be_bytes())?;
+            writer.write_all(&header.chunk_type.bytes())?;
+            copy_exact(reader, writer, header.data_and_crc_length())?;
+        }
+    }
+
+    Ok((
+        removed_chunk.ok_or(PngError::ChunkNotFoundError)?,
+        count - 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_png_bytes_eq;
+    use std::{io::Cursor, str::FromStr};
+
+    #[test]
+    fn test_copy_exact_base64_large_data() {
+        use base64::Engine;
+
+        // not a multiple of 3 bytes, so the padding at the end is also checked
+        let data = (0..3_000_001u32)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+        let mut output = vec![];
+
+        copy_exact_base64(&mut Cursor::new(&data), &mut output, data.len() as u64).unwrap();
+
+        assert_eq!(output, STANDARD.encode(&data).into_bytes());
+        assert!(
+            copy_exact_base64(&mut Cursor::new(&data), &mut vec![], data.len() as u64 + 1).is_err()
+        );
+    }
+
+    #[test]
+    fn test_remove_chunk_matches_buffered_remove() {
+        let mut png = testing_png();
+        let mut output = vec![];
+        let (removed_chunk, remaining_chunks) =
+            remove_chunk(&mut Cursor::new(png.as_bytes()), &mut output, "TeSt").unwrap();
+        let expected_chunk = png.remove_chunk("TeSt").unwrap();
+
+        assert_eq!(removed_chunk.as_bytes(), expected_chunk.as_bytes());
+        assert_eq!(remaining_chunks, 3);
+        assert_png_bytes_eq(&Png::try_from(&output[..]).unwrap(), &png);
+    }
+
+    #[test]
+    fn test_remove_chunk_not_found() {
+        let png = testing_png();
+        let mut output = vec![];
+
+        assert!(remove_chunk(&mut Cursor::new(png.as_bytes()), &mut output, "LASt").is_err());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_remove_chunk_invalid_header() {
+        let mut bytes = testing_png().as_bytes();
+        let mut output = vec![];
+
+        bytes[0] = 0;
+        assert!(remove_chunk(&mut Cursor::new(bytes), &mut output, "TeSt").is_err());
+    }
+
+    #[test]
+    fn test_remove_chunk_truncated_stream() {
+        let bytes = testing_png().as_bytes();
+        let mut output = vec![];
+
+        assert!(remove_chunk(
+            &mut Cursor::new(&bytes[..bytes.len() - 2]),
+            &mut output,
+            "FrSt"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_png() {
+        let png = testing_png();
+        let bytes = png.as_bytes();
+        let options = ParseOptions {
+            max_total_bytes: Some(bytes.len() as u64),
+        };
+
+        assert_png_bytes_eq(&read_png(&bytes[..], &options).unwrap(), &png);
+        assert_png_bytes_eq(
+            &read_png(&bytes[..], &ParseOptions::default()).unwrap(),
+            &png,
+        );
+    }
+
+    #[test]
+    fn test_read_chunk_headers_matches_full_parse() {
+        let png = testing_png();
+        let headers = read_chunk_headers(&mut Cursor::new(png.as_bytes())).unwrap();
+
+        assert_eq!(
+            headers,
+            png.chunks()
+                .iter()
+                .map(|c| (c.length(), c.chunk_type().clone()))
+                .collect::<Vec<(u32, ChunkType)>>()
+        );
+    }
+
+    #[test]
+    fn test_read_png_exceeding_budget() {
+        let bytes = testing_png().as_bytes();
+        let options = ParseOptions {
+            max_total_bytes: Some(bytes.len() as u64 - 1),
+        };
+
+        assert!(read_png(&bytes[..], &options).is_err());
+    }
+
+    #[test]
+    fn test_read_png_aborts_before_reading_declared_length() {
+        /*
+            the chunk declares 64 MiB of data which never get read nor allocated, since the budget
+            is exceeded as soon as its header is read
+        */
+        let mut prefix = Png::STANDARD_HEADER.to_vec();
+
+        prefix.extend_from_slice(&(64 * 1024 * 1024u32).to_be_bytes());
+        prefix.extend_from_slice(b"biGd");
+
+        let mut reader = SyntheticChunkReader {
+            prefix,
+            data_length: 64 * 1024 * 1024,
+            position: 0,
+        };
+        let options = ParseOptions {
+            max_total_bytes: Some(1024),
+        };
+        let result = read_png(&mut reader, &options);
+
+        assert!(matches!(
+            result.err().unwrap().downcast_ref::<StreamError>(),
+            Some(StreamError::LimitExceeded(1024))
+        ));
+        assert_eq!(reader.position, 16);
+    }
+
+    #[test]
+    fn test_extract_chunk_data() {
+        let png = testing_png();
+        let mut output = vec![];
+        let length =
+            extract_chunk_data(&mut Cursor::new(png.as_bytes()), &mut output, "TeSt").unwrap();
+
+        assert_eq!(length, 17);
+        assert_eq!(output, png.chunk_by_type("TeSt").unwrap().data());
+    }
+
+    #[test]
+    fn test_extract_chunk_data_not_found() {
+        let mut output = vec![];
+
+        assert!(extract_chunk_data(
+            &mut Cursor::new(testing_png().as_bytes()),
+            &mut output,
+            "LASt"
+        )
+        .is_err());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_extract_large_chunk_data_with_bounded_memory() {
+        /*
+            the stream is generated on the fly and the output only counts the bytes it receives,
+            so the 64 MiB of chunk data never exist in memory at the same time
+        */
+        const LENGTH: u64 = 64 * 1024 * 1024;
+        let mut prefix = Png::STANDARD_HEADER.to_vec();
+
+        prefix.extend_from_slice(&(LENGTH as u32).to_be_bytes());
+        prefix.extend_from_slice(b"biGd");
+
+        let mut reader = SyntheticChunkReader {
+            prefix,
+            data_length: LENGTH,
+            position: 0,
+        };
+        let mut output = CountingWriter(0);
+
+        assert_eq!(
+            extract_chunk_data(&mut reader, &mut output, "biGd").unwrap(),
+            LENGTH
+        );
+        assert_eq!(output.0, LENGTH);
+    }
+
+    #[test]
+    fn test_insert_before_end_matches_buffered_insert() {
+        let chunk = Chunk::new(ChunkType::from_str("TeSt").unwrap(), b"Watermark".to_vec());
+        let mut png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("IHDR").unwrap(), vec![0; 13]),
+            Chunk::new(
+                ChunkType::from_str("IDAT").unwrap(),
+                vec![0; 4 * 1024 * 1024],
+            ),
+            Chunk::new(ChunkType::from_str("IEND").unwrap(), vec![]),
+            Chunk::new(
+                ChunkType::from_str("afTr").unwrap(),
+                b"After the end".to_vec(),
+            ),
+        ]);
+        let mut file = Cursor::new(png.as_bytes());
+        let chunk_types = insert_before_end(&mut file, &chunk).unwrap();
+
+        png.insert_before_end(chunk);
+        assert_eq!(chunk_types, vec!["IHDR", "IDAT", "TeSt", "IEND", "afTr"]);
+        assert_png_bytes_eq(&Png::try_from(&file.into_inner()[..]).unwrap(), &png);
+    }
+
+    #[test]
+    fn test_insert_before_end_without_iend() {
+        let chunk = Chunk::new(ChunkType::from_str("LASt").unwrap(), vec![]);
+        let mut png = testing_png();
+        let mut file = Cursor::new(png.as_bytes());
+
+        insert_before_end(&mut file, &chunk).unwrap();
+        png.insert_before_end(chunk);
+        assert_png_bytes_eq(&Png::try_from(&file.into_inner()[..]).unwrap(), &png);
+    }
+
+    #[test]
+    fn test_insert_before_end_truncated_file() {
+        let chunk = Chunk::new(ChunkType::from_str("LASt").unwrap(), vec![]);
+        let bytes = testing_png().as_bytes();
+        let truncated = bytes[..bytes.len() - 10].to_vec();
+        let mut file = Cursor::new(truncated.clone());
+
+        // the last chunk is 12 + 24 bytes long, but only 26 of them are left
+        assert!(matches!(
+            insert_before_end(&mut file, &chunk)
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(PngError::TruncatedChunk(offset, 36, 26)) if *offset == bytes.len() - 36
+        ));
+        assert_eq!(file.into_inner(), truncated);
+    }
+
+    #[test]
+    fn test_seeking_past_truncated_chunk() {
+        let bytes = testing_png().as_bytes();
+        let truncated = &bytes[..bytes.len() - 10];
+
+        assert!(read_chunk_headers(&mut Cursor::new(truncated)).is_err());
+        assert!(find_chunk_data(&mut Cursor::new(truncated), "LASt").is_err());
+        assert!(remove_chunk(&mut Cursor::new(truncated), &mut vec![], "FrSt").is_err());
+    }
+
+    struct SyntheticChunkReader {
+        prefix: Vec<u8>,
+        data_length: u64,
+        position: u64,
+    }
+
+    impl SyntheticChunkReader {
+        fn len(&self) -> u64 {
+            // the crc of the chunk is left as zeroes since it's never verified
+            self.prefix.len() as u64 + self.data_length + 4
+        }
+    }
+
+    impl Read for SyntheticChunkReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let remaining = self.len().saturating_sub(self.position);
+            let count = buf.len().min(remaining as usize);
+
+            for (i, byte) in buf[..count].iter_mut().enumerate() {
+                *byte = self
+                    .prefix
+                    .get(self.position as usize + i)
+                    .copied()
+                    .unwrap_or(0);
+            }
+
+            self.position += count as u64;
+
+            Ok(count)
+        }
+    }
+
+    impl Seek for SyntheticChunkReader {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.position = match pos {
+                SeekFrom::Start(n) => n,
+                SeekFrom::End(n) => (self.len() as i64 + n) as u64,
+                SeekFrom::Current(n) => (self.position as i64 + n) as u64,
+            };
+
+            Ok(self.position)
+        }
+    }
+
+    struct CountingWriter(u64);
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len() as u64;
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn testing_png() -> Png {
+        Png::from_chunks(
+            [
+                ("FrSt", "I am the first chunk"),
+                ("TeSt", "I am a test chunk"),
+                ("miDl", "I am another chunk"),
+                ("TeSt", "I am the last test chunk"),
+            ]
+            .iter()
+            .map(|(t, d)| Chunk::new(ChunkType::from_str(t).unwrap(), d.as_bytes().to_vec()))
+            .collect(),
+        )
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{chunk::Chunk, compression};
+use anyhow::Result;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Charset {
+    #[default]
+    Utf8,
+    Latin1,
+    Auto,
+    Utf16Le,
+    Utf16Be,
+}
+
+#[derive(Debug, Error)]
+pub enum CharsetError {
+    #[error("Unsupported charset {0:?}, expected \"utf8\", \"latin1\", \"auto\", \"utf16le\" or \"utf16be\"")]
+    UnsupportedCharset(String),
+    #[error("UTF-16 text needs an even number of bytes, found {0}")]
+    OddUtf16Length(usize),
+}
+
+#[derive(Debug, Error)]
+pub enum TextChunkError {
+    #[error("The {0} chunk is missing a null separator")]
+    MissingSeparator(String),
+    #[error("Unsupported compression method {0} in a text chunk, expected 0 (zlib)")]
+    UnsupportedCompressionMethod(u8),
+    #[error("The character {0:?} is not part of Latin-1, use an iTXt chunk for UTF-8 text")]
+    NonLatin1Character(char),
+}
+
+impl Charset {
+    pub fn decode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Self::Utf8 => Ok(String::from_utf8(bytes.to_vec())?),
+            Self::Latin1 => Ok(decode_latin1(bytes)),
+            // any byte sequence is valid Latin-1, so it's only used when UTF-8 fails
+            Self::Auto => {
+                Ok(String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| decode_latin1(bytes)))
+            }
+            Self::Utf16Le => decode_utf16(bytes, u16::from_le_bytes),
+            Self::Utf16Be => decode_utf16(bytes, u16::from_be_bytes),
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String> {
+    // not part of the PNG specification, but some tools store their text this way
+    if !bytes.len().is_multiple_of(2) {
+        return Err(CharsetError::OddUtf16Length(bytes.len()).into());
+    }
+
+    let units = bytes
+        .chunks_exact(2)
+        .map(|b| from_bytes([b[0], b[1]]))
+        .collect::<Vec<u16>>();
+
+    Ok(String::from_utf16(&units)?)
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.Anc-text
+
+        the text of tEXt and zTXt chunks is encoded in ISO 8859-1 (Latin-1), whose 256 code points
+        are the same as the first 256 ones of Unicode
+    */
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+pub fn is_latin1_chunk(chunk_type: &str) -> bool {
+    chunk_type == "tEXt" || chunk_type == "zTXt"
+}
+
+pub fn encode_latin1(text: &str) -> Result<Vec<u8>, TextChunkError> {
+    // the first 256 code points of Unicode map to the Latin-1 byte with the same value
+    text.chars()
+        .map(|c| u8::try_from(c).map_err(|_| TextChunkError::NonLatin1Character(c)))
+        .collect()
+}
+
+pub fn decode_text_chunk(chunk: &Chunk) -> Result<Option<(String, String)>> {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.Anc-text
+
+        every text chunk starts with a null-terminated Latin-1 keyword, then
+        - tEXt: the Latin-1 text
+        - zTXt: the compression method (0 for zlib) and the compressed Latin-1 text
+        - iTXt: the compression flag and method, the null-terminated language tag and translated
+          keyword, and the UTF-8 text which is compressed only if the flag is 1
+    */
+    let chunk_type = chunk.chunk_type().to_string();
+    let split = |data: &[u8]| match data.iter().position(|&b| b == 0) {
+        Some(i) => Ok((data[..i].to_vec(), data[i + 1..].to_vec())),
+        None => Err(TextChunkError::MissingSeparator(chunk_type.clone())),
+    };
+    let inflate = |method: u8, data: &[u8]| match method {
+        0 => compression::inflate(data),
+        _ => Err(TextChunkError::UnsupportedCompressionMethod(method).into()),
+    };
+
+    let (keyword, text) = match chunk_type.as_str() {
+        "tEXt" => {
+            let (keyword, text) = split(chunk.data())?;
+
+            (keyword, decode_latin1(&text))
+        }
+        "zTXt" => {
+            let (keyword, rest) = split(chunk.data())?;
+            let (&method, compressed) = rest
+                .split_first()
+                .ok_or_else(|| TextChunkError::MissingSeparator(chunk_type.clone()))?;
+
+            (keyword, decode_latin1(&inflate(method, compressed)?))
+        }
+        "iTXt" => {
+            let (keyword, rest) = split(chunk.data())?;
+            let (flags, rest) = rest
+                .split_at_checked(2)
+                .ok_or_else(|| TextChunkError::MissingSeparator(chunk_type.clone()))?;
+            let (_language_tag, rest) = split(rest)?;
+            let (_translated_keyword, text) = split(&rest)?;
+            let text = match flags {
+                [1, method] => inflate(*method, &text)?,
+                _ => text,
+            };
+
+            (keyword, String::from_utf8(text)?)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some((decode_latin1(&keyword), text)))
+}
+
+impl FromStr for Charset {
+    type Err = CharsetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Ok(Self::Utf8),
+            "latin1" | "latin-1" | "iso-8859-1" => Ok(Self::Latin1),
+            "auto" => Ok(Self::Auto),
+            "utf16le" | "utf-16le" => Ok(Self::Utf16Le),
+            "utf16be" | "utf-16be" => Ok(Self::Utf16Be),
+            _ => Err(CharsetError::UnsupportedCharset(s.to_string())),
+        }
+    }
+}
+
+impl Display for Charset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "utf8"),
+            Self::Latin1 => write!(f, "latin1"),
+            Self::Auto => write!(f, "auto"),
+            Self::Utf16Le => write!(f, "utf16le"),
+            Self::Utf16Be => write!(f, "utf16be"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_type::ChunkType;
+
+    // "Café crème" encoded in Latin-1
+    const LATIN1_TEXT: [u8; 10] = [67, 97, 102, 233, 32, 99, 114, 232, 109, 101];
+
+    #[test]
+    fn test_decode_latin1() {
+        assert_eq!(Charset::Latin1.decode(&LATIN1_TEXT).unwrap(), "Café crème");
+        assert!(Charset::Utf8.decode(&LATIN1_TEXT).is_err());
+    }
+
+    #[test]
+    fn test_decode_auto() {
+        assert_eq!(Charset::Auto.decode(&LATIN1_TEXT).unwrap(), "Café crème");
+        assert_eq!(
+            Charset::Auto.decode("Café crème".as_bytes()).unwrap(),
+            "Café crème"
+        );
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let le = [0x3d, 0xd8, 0x80, 0xdc, b'a', 0];
+        let be = [0xd8, 0x3d, 0xdc, 0x80, 0, b'a'];
+
+        assert_eq!(Charset::Utf16Le.decode(&le).unwrap(), "\u{1f480}a");
+        assert_eq!(Charset::Utf16Be.decode(&be).unwrap(), "\u{1f480}a");
+        assert!(matches!(
+            Charset::Utf16Le
+                .decode(&le[..5])
+                .err()
+                .unwrap()
+                .downcast_ref(),
+            Some(CharsetError::OddUtf16Length(5))
+        ));
+        // a lone high surrogate
+        assert!(Charset::Utf16Le.decode(&le[..2]).is_err());
+    }
+
+    #[test]
+    fn test_charset_from_str() {
+        for charset in [
+            Charset::Utf8,
+            Charset::Latin1,
+            Charset::Auto,
+            Charset::Utf16Le,
+            Charset::Utf16Be,
+        ] {
+            assert_eq!(Charset::from_str(&charset.to_string()).unwrap(), charset);
+        }
+
+        assert_eq!(Charset::from_str("ISO-8859-1").unwrap(), Charset::Latin1);
+        assert!(Charset::from_str("utf16").is_err());
+    }
+
+    #[test]
+    fn test_decode_text_chunks() {
+        let mut ztxt_data = b"Author\0\0".to_vec();
+
+        ztxt_data.extend(compression::deflate(&LATIN1_TEXT).unwrap());
+
+        let text = Chunk::from_strings("tEXt", "Title\0I am a title").unwrap();
+        let ztxt = Chunk::new(ChunkType::from_str("zTXt").unwrap(), ztxt_data);
+        let itxt = Chunk::from_strings("iTXt", "Comment\0\0\0en\0Commento\0Café").unwrap();
+
+        assert_eq!(
+            decode_text_chunk(&text).unwrap().unwrap(),
+            (String::from("Title"), String::from("I am a title"))
+        );
+        assert_eq!(
+            decode_text_chunk(&ztxt).unwrap().unwrap(),
+            (String::from("Author"), String::from("Café crème"))
+        );
+        assert_eq!(
+            decode_text_chunk(&itxt).unwrap().unwrap(),
+            (String::from("Comment"), String::from("Café"))
+        );
+        assert!(decode_text_chunk(&Chunk::from_strings("RuSt", "").unwrap())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_encode_latin1() {
+        assert_eq!(encode_latin1("Café crème").unwrap(), LATIN1_TEXT);
+        assert!(matches!(
+            encode_latin1("I am a 🦀"),
+            Err(TextChunkError::NonLatin1Character('🦀'))
+        ));
+    }
+}
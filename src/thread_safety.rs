@@ -0,0 +1,14 @@
+/*
+    Png, Chunk and ChunkType only own plain bytes and vectors of them, so they can be shared and
+    moved across threads, these assertions make sure no future field silently breaks that
+*/
+use crate::{chunk::Chunk, chunk_type::ChunkType, png::Png};
+
+fn _assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_types_are_send_and_sync() {
+    _assert_send_sync::<Png>();
+    _assert_send_sync::<Chunk>();
+    _assert_send_sync::<ChunkType>();
+}
@@ -0,0 +1,321 @@
+use crate::{png::Png, spec::SpecVersion};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Issue {
+    pub chunk_index: Option<usize>,
+    pub chunk_type: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    ok: bool,
+    issues: &'a [Issue],
+}
+
+impl Issue {
+    fn file(severity: Severity, message: String) -> Self {
+        Self {
+            chunk_index: None,
+            chunk_type: None,
+            severity,
+            message,
+        }
+    }
+
+    fn chunk(index: usize, severity: Severity, message: String) -> Self {
+        Self {
+            chunk_index: Some(index),
+            chunk_type: None,
+            severity,
+            message,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.chunk_index {
+            Some(index) => write!(f, "Chunk {index}: {}", self.message),
+            None => write!(f, "File: {}", self.message),
+        }
+    }
+}
+
+pub fn verify(png: &Png, spec: SpecVersion) -> Vec<Issue> {
+    /*
+        decoders can still display a file with warnings, like unknown chunks, reserved bits or
+        chunks after IEND, while errors in its structure prevent it from being displayed at all
+    */
+    let types = png
+        .chunks()
+        .iter()
+        .map(|c| c.chunk_type().to_string())
+        .collect::<Vec<String>>();
+    let mut issues = vec![];
+
+    for (i, chunk) in png.chunks().iter().enumerate() {
+        let chunk_type = chunk.chunk_type();
+
+        if !chunk.has_valid_crc() {
+            issues.push(Issue::chunk(
+                i,
+                Severity::Error,
+                format!(
+                    "{chunk_type} has stored CRC {:#010x} but computed CRC {:#010x}",
+                    chunk.crc(),
+                    chunk.calculated_crc()
+                ),
+            ));
+        }
+
+        if !chunk_type.is_valid() {
+            issues.push(Issue::chunk(
+                i,
+                Severity::Warning,
+                format!("{chunk_type} has the reserved bit set"),
+            ));
+        }
+
+        if !spec.is_known(&types[i]) {
+            let kind = if chunk_type.is_critical() {
+                "critical"
+            } else {
+                "ancillary"
+            };
+
+            issues.push(Issue::chunk(
+                i,
+                Severity::Warning,
+                format!("{chunk_type} is an unknown {kind} chunk for PNG {spec}"),
+            ));
+        }
+    }
+
+    issues.extend(verify_structure(&types));
+
+    // the type of the chunk each issue refers to is filled in once for all of them
+    for issue in &mut issues {
+        issue.chunk_type = issue.chunk_index.map(|i| types[i].clone());
+    }
+
+    issues
+}
+
+pub fn report_json(issues: &[Issue], ok: bool) -> Result<String> {
+    Ok(serde_json::to_string(&Report { ok, issues })?)
+}
+
+fn verify_structure(types: &[String]) -> Vec<Issue> {
+    /*
+        from http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.Summary-of-standard-chunks
+
+        IHDR must be the first chunk and IEND the last one, there must be at least one IDAT and the
+        optional PLTE must come before it, and multiple IDAT chunks must be consecutive
+    */
+    let positions = |name: &str| {
+        types
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| *t == name)
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>()
+    };
+    let ihdr = positions("IHDR");
+    let idat = positions("IDAT");
+    let iend = positions("IEND");
+    let mut issues = vec![];
+
+    if ihdr.first() != Some(&0) {
+        issues.push(Issue::file(
+            Severity::Error,
+            String::from("the first chunk must be IHDR"),
+        ));
+    }
+
+    for &i in ihdr.iter().skip(1) {
+        issues.push(Issue::chunk(
+            i,
+            Severity::Error,
+            String::from("IHDR must appear only once"),
+        ));
+    }
+
+    match iend.first() {
+        Some(&first_iend) => {
+            for (i, t) in types.iter().enumerate().skip(first_iend + 1) {
+                issues.push(Issue::chunk(
+                    i,
+                    Severity::Warning,
+                    format!("{t} appears after IEND"),
+                ));
+            }
+        }
+        None => issues.push(Issue::file(
+            Severity::Error,
+            String::from("the IEND chunk is missing"),
+        )),
+    }
+
+    match idat.first() {
+        Some(&first_idat) => {
+            for i in positions("PLTE").into_iter().filter(|&i| i > first_idat) {
+                issues.push(Issue::chunk(
+                    i,
+                    Severity::Error,
+                    String::from("PLTE must precede the first IDAT"),
+                ));
+            }
+        }
+        None => issues.push(Issue::file(
+            Severity::Error,
+            String::from("the IDAT chunk is missing"),
+        )),
+    }
+
+    for pair in idat.windows(2).filter(|p| p[1] > p[0] + 1) {
+        issues.push(Issue::chunk(
+            pair[1],
+            Severity::Error,
+            format!(
+                "IDAT chunks must be consecutive, found {} before this one",
+                types[pair[1] - 1]
+            ),
+        ));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk::Chunk, chunk_type::ChunkType};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_verify_valid_png() {
+        let png = testing_png(&["IHDR", "IDAT", "IEND"]);
+
+        assert!(verify(&png, SpecVersion::V1_2).is_empty());
+        assert!(verify(&png, SpecVersion::V3).is_empty());
+    }
+
+    #[test]
+    fn test_verify_exif_depends_on_spec() {
+        let png = testing_png(&["IHDR", "eXIf", "IDAT", "IEND"]);
+        let issues = verify(&png, SpecVersion::V1_2);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].chunk_index, Some(1));
+        assert!(issues[0].message.contains("unknown"));
+        assert!(verify(&png, SpecVersion::V3).is_empty());
+    }
+
+    #[test]
+    fn test_verify_custom_chunk_is_unknown() {
+        let png = testing_png(&["IHDR", "IDAT", "ruSt", "IEND"]);
+        let issues = verify(&png, SpecVersion::V3);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].chunk_index, Some(2));
+    }
+
+    #[test]
+    fn test_verify_reserved_bit() {
+        let png = testing_png(&["IHDR", "IDAT", "ruse", "IEND"]);
+        let issues = verify(&png, SpecVersion::V3);
+
+        assert!(issues
+            .iter()
+            .any(|i| i.chunk_index == Some(2) && i.message.contains("reserved bit")));
+    }
+
+    #[test]
+    fn test_verify_structure() {
+        let png = testing_png(&["IDAT", "PLTE", "IHDR", "IEND", "tEXt"]);
+        let messages = verify(&png, SpecVersion::V3)
+            .into_iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                "File: the first chunk must be IHDR",
+                "Chunk 4: tEXt appears after IEND",
+                "Chunk 1: PLTE must precede the first IDAT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_idat_contiguity() {
+        let png = testing_png(&["IHDR", "IDAT", "tEXt", "IDAT", "IDAT", "IEND"]);
+        let issues = verify(&png, SpecVersion::V3);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(
+            issues[0].to_string(),
+            "Chunk 3: IDAT chunks must be consecutive, found tEXt before this one"
+        );
+        assert!(verify(
+            &testing_png(&["IHDR", "IDAT", "IDAT", "IEND"]),
+            SpecVersion::V3
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_verify_severity() {
+        let png = testing_png(&["IHDR", "IDAT", "ruSt", "IEND", "tEXt"]);
+        let severities = verify(&png, SpecVersion::V3)
+            .into_iter()
+            .map(|i| i.severity)
+            .collect::<Vec<Severity>>();
+
+        assert_eq!(severities, vec![Severity::Warning, Severity::Warning]);
+        assert!(verify(&testing_png(&["IHDR"]), SpecVersion::V3)
+            .iter()
+            .all(|i| i.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_verify_missing_chunks() {
+        let png = testing_png(&["IHDR"]);
+        let issues = verify(&png, SpecVersion::V3);
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.chunk_index.is_none()));
+    }
+
+    fn testing_png(types: &[&str]) -> Png {
+        Png::from_chunks(
+            types
+                .iter()
+                .map(|t| Chunk::new(ChunkType::from_str(t).unwrap(), vec![]))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,17 @@
+use std::{fs, process::Command};
+
+const INVALID_FILE_NAME: &str = "cli_invalid.png";
+
+#[test]
+fn test_verify_invalid_file_exit_code() {
+    fs::write(INVALID_FILE_NAME, b"I am not a PNG file").unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_pngme"))
+        .args(["verify", INVALID_FILE_NAME])
+        .status()
+        .unwrap();
+
+    fs::remove_file(INVALID_FILE_NAME).unwrap();
+
+    assert_eq!(status.code(), Some(1));
+}
@@ -0,0 +1,37 @@
+use pngme::{Chunk, ChunkType, Png, PngError};
+use std::str::FromStr;
+
+#[test]
+fn test_round_trip_through_public_api() {
+    let mut png = Png::from_chunks(vec![
+        Chunk::from_strings("IHDR", "").unwrap(),
+        Chunk::from_strings("IEND", "").unwrap(),
+    ]);
+
+    png.insert_before_end(Chunk::new(
+        ChunkType::from_str("RuSt").unwrap(),
+        b"I am a secret message".to_vec(),
+    ));
+
+    let bytes = png.as_bytes();
+    let mut parsed = Png::try_from(&bytes[..]).unwrap();
+
+    assert_eq!(&bytes[..8], Png::STANDARD_HEADER);
+    assert_eq!(parsed.chunks().len(), 3);
+    assert_eq!(
+        parsed.chunk_by_type("RuSt").unwrap().data(),
+        b"I am a secret message"
+    );
+    assert_eq!(parsed.as_bytes(), bytes);
+
+    parsed.remove_chunk("RuSt").unwrap();
+
+    assert!(matches!(
+        parsed.remove_chunk("RuSt").err().unwrap().downcast_ref(),
+        Some(PngError::ChunkNotFoundError)
+    ));
+    assert!(matches!(
+        Png::try_from(&bytes[1..]),
+        Err(PngError::InvalidHeaderError)
+    ));
+}